@@ -0,0 +1,76 @@
+//! Feeds arbitrary input through point parsing and solving, which must only ever
+//! fail cleanly with `None`, an error or a 0 result, never panic
+//!
+//! The first byte picks the path, the rest is its input:
+//! 0: `x|y` decimal coordinates for `try_grumpkin_str_to_point`
+//! 1: arkworks serialized bytes, compressed or not, solved if valid
+//! 2: little endian u32 scalar then a u8 steps_count for `grumpkin_bsgs`
+//! 3: raw coordinates for `Affine::new_unchecked`, solved even if off-curve
+//!
+//! Run with `cargo fuzz run parse_and_solve`, seeds are in `corpus/parse_and_solve`
+
+#![no_main]
+
+use ark_ff::PrimeField;
+use ark_grumpkin::{Affine, Fq};
+use ark_serialize::CanonicalDeserialize;
+use baby_giant_core::{
+    BabyGiantOps,
+    impls::grumpkin::{
+        GrumpkinBabyGiant, g, grumpkin_bsgs, grumpkin_scalar_base, try_grumpkin_str_to_point,
+    },
+};
+use libfuzzer_sys::fuzz_target;
+
+/// Small enough to keep each solve fast
+const FUZZ_STEPS: u64 = 16;
+
+/// Only checks for panics, x-only keys can legitimately report k for -k·g or for an
+/// off-curve point sharing an x-coordinate with the table
+fn solve(target: Affine) {
+    let mut grumpy_bsgs = GrumpkinBabyGiant::new(FUZZ_STEPS);
+    let _ = grumpy_bsgs.run(g(), target);
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&mode, rest)) = data.split_first() else {
+        return;
+    };
+
+    match mode % 4 {
+        0 => {
+            let text = String::from_utf8_lossy(rest);
+            let (x, y) = text.split_once('|').unwrap_or((&text, ""));
+            if let Ok(target) = try_grumpkin_str_to_point(x, y) {
+                assert!(target.is_on_curve());
+                solve(target);
+            }
+        }
+        1 => {
+            let target = Affine::deserialize_compressed(rest)
+                .or_else(|_| Affine::deserialize_uncompressed(rest));
+            if let Ok(target) = target {
+                solve(target);
+            }
+        }
+        2 => {
+            let Some((scalar, steps)) = rest.split_first_chunk::<4>() else {
+                return;
+            };
+            let steps = u64::from(steps.first().copied().unwrap_or(0));
+            let scalar = u64::from(u32::from_le_bytes(*scalar));
+            let res = grumpkin_bsgs(grumpkin_scalar_base(scalar), steps);
+            if scalar < steps * steps {
+                assert_eq!(res, scalar);
+            }
+        }
+        _ => {
+            let (x, y) = rest.split_at(rest.len().min(32));
+            let point = Affine::new_unchecked(
+                Fq::from_le_bytes_mod_order(x),
+                Fq::from_le_bytes_mod_order(y),
+            );
+            solve(point);
+        }
+    }
+});
@@ -1,5 +1,8 @@
+use std::collections::HashMap;
 use std::hash::Hash;
-use std::ops::AddAssign;
+use std::ops::{Add, AddAssign, Sub};
+
+use crate::BsgsError;
 
 /// A trait for types that can be used with the baby-step giant-step algorithm
 /// This algorithm solves the discrete logarithm problem: finding x where target = base^x
@@ -11,25 +14,130 @@ pub trait BabyGiantOps {
     /// The group element type (e.g., points on an elliptic curve)
     type El;
 
+    /// The baby step table key, e.g. the element itself or one of its coordinates
+    type Key: Eq + Hash;
+
     fn steps_count(&self) -> Self::Scalar;
 
+    /// Maps an element to its table key
+    fn key(&self, el: &Self::El) -> Self::Key;
+
+    /// Canonical bytes of an element, for keying, merging and serializing tables the
+    /// same way across curves. Defaults to the table key's bytes
+    fn element_bytes(&self, el: &Self::El) -> Vec<u8>
+    where
+        Self::Key: AsRef<[u8]>,
+    {
+        self.key(el).as_ref().to_vec()
+    }
+
+    /// Stores baby step k under its key, impls decide which index wins on a collision
+    fn store_baby_step(&mut self, key: Self::Key, baby_step: Self::Scalar);
+
+    /// Empties the baby step table before a rebuild
+    fn clear_baby_steps(&mut self);
+
     /// Computes and stores all baby steps
-    /// Returns a map from group elements to their corresponding scalar values
-    fn baby_steps(&mut self, base: &Self::El);
+    /// Invariant: entry k maps k·base to k, for k in 0..steps_count (starting at the identity)
+    /// Defaults to walking from the identity with `el_operation`, storing each `key`
+    fn baby_steps(&mut self, base: &Self::El)
+    where
+        Self::Scalar: Clone + PartialOrd + From<u32> + AddAssign,
+    {
+        self.clear_baby_steps();
+        let steps_count = self.steps_count();
+        let mut current = self.identity();
+        let mut baby_step = Self::Scalar::from(0);
+        while baby_step < steps_count {
+            let key = self.key(&current);
+            self.store_baby_step(key, baby_step.clone());
+            current = self.el_operation(&current, base);
+            baby_step += Self::Scalar::from(1);
+        }
+    }
+
+    /// Maps equivalent representations of an element to a single one before keying
+    /// Called by `baby_steps` and `in_baby_steps`, defaults to a no-op
+    fn canonical(&self, el: &Self::El) -> Self::El
+    where
+        Self::El: Clone,
+    {
+        el.clone()
+    }
 
     /// Checks if the given element is in the precomputed baby steps
     /// Returns the corresponding scalar value if found or None
-    fn in_baby_steps(&self, target: &Self::El) -> Option<&Self::Scalar>;
+    fn in_baby_steps(&self, target: &Self::El) -> Option<Self::Scalar>;
+
+    /// The identity element of the group (point at infinity for elliptic curves)
+    fn identity(&self) -> Self::El;
 
     /// Defines the group operation between two elements (addition for elliptic curves)
     fn el_operation(&self, lhs: &Self::El, rhs: &Self::El) -> Self::El;
 
+    /// Computes k·el, defaults to double-and-add over `el_operation`
+    /// Enables skip-ahead without looping k times
+    fn scalar_mul(&self, el: &Self::El, k: u64) -> Self::El
+    where
+        Self::El: Clone,
+    {
+        let mut result = self.identity();
+        let mut doubling = el.clone();
+        let mut k = k;
+        while k > 0 {
+            if k & 1 == 1 {
+                result = self.el_operation(&result, &doubling);
+            }
+            doubling = self.el_operation(&doubling, &doubling);
+            k >>= 1;
+        }
+        result
+    }
+
+    /// Maps an element into the prime-order subgroup by multiplying by the cofactor
+    /// Defaults to a no-op for prime-order groups
+    fn clear_cofactor(&self, el: &Self::El) -> Self::El
+    where
+        Self::El: Clone,
+    {
+        el.clone()
+    }
+
+    /// Quick necessary check that target can be a multiple of base
+    /// If clearing the cofactor sends base to the identity, base lies in the small cofactor
+    /// subgroup and so must target. Always true for prime-order groups and non-identity bases
+    fn same_subgroup(&self, base: &Self::El, target: &Self::El) -> bool
+    where
+        Self::El: Clone + PartialEq,
+    {
+        self.clear_cofactor(base) != self.identity()
+            || self.clear_cofactor(target) == self.identity()
+    }
+
     /// Computes the giant step base: typically -(m·base) for a chosen m
     fn giant_step_jump(&self, base: &Self::El) -> Self::El;
 
     /// Converts raw baby and giant step values into the final scalar result
     fn process_result(&self, baby: &Self::Scalar, giant: &Self::Scalar) -> Self::Scalar;
 
+    /// Adds two scalars, used by the interval and offset machinery
+    /// Defaults to plain integer addition
+    fn scalar_add(&self, a: &Self::Scalar, b: &Self::Scalar) -> Self::Scalar
+    where
+        Self::Scalar: Clone + Add<Output = Self::Scalar>,
+    {
+        a.clone() + b.clone()
+    }
+
+    /// Subtracts scalar `b` from `a`, used by the interval and offset machinery
+    /// Defaults to plain integer subtraction
+    fn scalar_sub(&self, a: &Self::Scalar, b: &Self::Scalar) -> Self::Scalar
+    where
+        Self::Scalar: Clone + Sub<Output = Self::Scalar>,
+    {
+        a.clone() - b.clone()
+    }
+
     /// The main BSGS algorithm implementation
     /// Solves for x in the equation target = x·base
     fn run(&mut self, base: Self::El, target: Self::El) -> Option<Self::Scalar>
@@ -37,11 +145,134 @@ pub trait BabyGiantOps {
         Self::El: Clone + Eq + Hash,
         Self::Scalar: Clone + PartialOrd + From<u32> + AddAssign,
     {
+        if let Some(res) = self.cached_result(&base, &target) {
+            return Some(res);
+        }
+
         // Precompute all baby steps and store in a hash map for O(1) lookups
         self.baby_steps(&base);
 
+        debug!("bsgs: giant walk started");
+        let steps = self.solve_steps(&base, target.clone());
+        debug!("bsgs: giant walk ended, found: {}", steps.is_some());
+        self.record_giant_steps(steps.as_ref().map(|(_, giant)| giant));
+
+        let res = steps.map(|(baby, giant)| self.process_result(&baby, &giant));
+        if let Some(res) = &res {
+            self.cache_result(&base, &target, res);
+        }
+        res
+    }
+
+    /// `run` converting the result into any type built from a scalar
+    /// e.g. `run_as::<Fr>` or `run_as::<u128>` for u64 scalars
+    fn run_as<O: From<Self::Scalar>>(&mut self, base: Self::El, target: Self::El) -> Option<O>
+    where
+        Self::El: Clone + Eq + Hash,
+        Self::Scalar: Clone + PartialOrd + From<u32> + AddAssign,
+    {
+        self.run(base, target).map(O::from)
+    }
+
+    /// Solves every target against one table built for base, results in input order
+    /// Duplicate targets (equal once `canonical`) are only searched for once
+    fn run_many(&mut self, base: Self::El, targets: &[Self::El]) -> Vec<Option<Self::Scalar>>
+    where
+        Self::El: Clone + Eq + Hash,
+        Self::Scalar: Clone + PartialOrd + From<u32> + AddAssign,
+    {
+        self.baby_steps(&base);
+
+        let mut solved: HashMap<Self::El, Option<Self::Scalar>> = HashMap::new();
+        targets
+            .iter()
+            .map(|target| {
+                let target = self.canonical(target);
+                if let Some(res) = solved.get(&target) {
+                    return res.clone();
+                }
+                let res = self.solve(&base, target.clone());
+                solved.insert(target, res.clone());
+                res
+            })
+            .collect()
+    }
+
+    /// `run` that fails with `NoSolution` without searching when `same_subgroup` rules
+    /// a solution out, and with `NotFound` when the search misses
+    fn try_run(&mut self, base: Self::El, target: Self::El) -> Result<Self::Scalar, BsgsError>
+    where
+        Self::El: Clone + Eq + Hash,
+        Self::Scalar: Clone + PartialOrd + From<u32> + AddAssign,
+    {
+        if !self.same_subgroup(&base, &target) {
+            return Err(BsgsError::NoSolution);
+        }
+        self.run(base, target).ok_or(BsgsError::NotFound)
+    }
+
+    /// Runs BSGS after clearing the cofactor of base and target
+    /// Recovers x modulo the prime subgroup order instead of a cofactor multiple off
+    fn run_cleared(&mut self, base: Self::El, target: Self::El) -> Option<Self::Scalar>
+    where
+        Self::El: Clone + Eq + Hash,
+        Self::Scalar: Clone + PartialOrd + From<u32> + AddAssign,
+    {
+        let base = self.clear_cofactor(&base);
+        let target = self.clear_cofactor(&target);
+        self.run(base, target)
+    }
+
+    /// Solves the log of the sum of `targets`, i.e. the sum of each target's log
+    /// Useful for decomposing aggregated commitments, None if any target has no solution
+    fn run_linear_combo(&mut self, base: Self::El, targets: &[Self::El]) -> Option<Self::Scalar>
+    where
+        Self::El: Clone + Eq + Hash,
+        Self::Scalar: Clone + PartialOrd + From<u32> + AddAssign,
+    {
+        self.baby_steps(&base);
+
+        let mut total: Self::Scalar = 0_u32.into();
+        for target in targets {
+            total += self.solve(&base, target.clone())?;
+        }
+        Some(total)
+    }
+
+    /// Previously found result for this base and target, consulted at the start of `run`
+    /// Default has no cache
+    fn cached_result(&mut self, _base: &Self::El, _target: &Self::El) -> Option<Self::Scalar> {
+        None
+    }
+
+    /// Stores a result found by `run`, default does nothing
+    fn cache_result(&mut self, _base: &Self::El, _target: &Self::El, _res: &Self::Scalar) {}
+
+    /// Runs the giant step phase against the already built baby steps
+    /// Lets one table built for `base` be reused across many targets
+    fn solve(&self, base: &Self::El, target: Self::El) -> Option<Self::Scalar>
+    where
+        Self::El: Clone + Eq + Hash,
+        Self::Scalar: Clone + PartialOrd + From<u32> + AddAssign,
+    {
+        self.solve_steps(base, target)
+            .map(|(baby, giant)| self.process_result(&baby, &giant))
+    }
+
+    /// Called by `run` with the giant step index of the match (None if not found)
+    /// Default does nothing, impls may store it for tuning
+    fn record_giant_steps(&mut self, _giant_steps: Option<&Self::Scalar>) {}
+
+    /// Giant step phase returning the raw (baby, giant) step pair of the match
+    fn solve_steps(&self, base: &Self::El, target: Self::El) -> Option<(Self::Scalar, Self::Scalar)>
+    where
+        Self::El: Clone + Eq + Hash,
+        Self::Scalar: Clone + PartialOrd + From<u32> + AddAssign,
+    {
         // Compute the giant step base (typically -(m·base))
-        let giant_step_jump = self.giant_step_jump(&base);
+        let giant_step_jump = self.giant_step_jump(base);
+        // A jump of identity means the order of base divides m, the walk can't move
+        let stationary = giant_step_jump == self.identity();
 
         // Start with the target element
         let mut current = target.clone();
@@ -52,15 +283,71 @@ pub trait BabyGiantOps {
         while giant_step < steps_count {
             // Check if current element matches any baby step
             if let Some(baby_step) = self.in_baby_steps(&current) {
-                // Found a match! Compute the final result
-                return Some(self.process_result(baby_step, &giant_step));
+                // Found a match! Return the steps for the final result
+                return Some((baby_step, giant_step));
+            }
+            if stationary {
+                break;
             }
             // Apply the giant step, target + giant_step·(-m·base))
             current = self.el_operation(&current, &giant_step_jump);
             giant_step += scalar_one.clone();
+            // Walk cycled through the whole subgroup of a small order base
+            if current == target {
+                break;
+            }
         }
 
         // No solution found
         None
     }
+
+    /// Solves for x in the equation target = x·base, knowing low <= x < high
+    /// Giant steps entirely below `low` are skipped without lookups and the search
+    /// continues past steps_count giant steps until `high` is reached
+    fn run_in_range(
+        &mut self,
+        base: Self::El,
+        target: Self::El,
+        low: Self::Scalar,
+        high: Self::Scalar,
+    ) -> Option<Self::Scalar>
+    where
+        Self::El: Clone + Eq + Hash,
+        Self::Scalar: Clone + PartialOrd + From<u32> + Add<Output = Self::Scalar> + AddAssign,
+    {
+        self.baby_steps(&base);
+
+        let giant_step_jump = self.giant_step_jump(&base);
+        let steps_count = self.steps_count();
+        let scalar_one: Self::Scalar = 1_u32.into();
+
+        let mut giant_step: Self::Scalar = 0_u32.into();
+        // Scalar offset giant_step·steps_count reached by `current`
+        let mut giant_offset: Self::Scalar = 0_u32.into();
+
+        // Skip giant steps lying entirely below `low`, jumping there in one go
+        let mut skipped = 0_u64;
+        while self.scalar_add(&giant_offset, &steps_count) <= low {
+            giant_offset = self.scalar_add(&giant_offset, &steps_count);
+            giant_step = self.scalar_add(&giant_step, &scalar_one);
+            skipped += 1;
+        }
+        let skip_jump = self.scalar_mul(&giant_step_jump, skipped);
+        let mut current = self.el_operation(&target, &skip_jump);
+
+        while giant_offset < high {
+            if let Some(baby_step) = self.in_baby_steps(&current) {
+                let res = self.process_result(&baby_step, &giant_step);
+                if res >= low && res < high {
+                    return Some(res);
+                }
+            }
+            current = self.el_operation(&current, &giant_step_jump);
+            giant_offset = self.scalar_add(&giant_offset, &steps_count);
+            giant_step = self.scalar_add(&giant_step, &scalar_one);
+        }
+
+        None
+    }
 }
@@ -0,0 +1,23 @@
+/// Solver options gathered in one place, for reproducible and scriptable solves
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BsgsConfig {
+    /// Number of baby steps, the search covers scalars below steps_count²
+    pub steps_count: u64,
+    /// Store every stride-th baby step, 1 stores them all
+    pub stride: u64,
+    /// Use multiple threads where supported
+    pub parallel: bool,
+    /// Giant steps between progress reports, 0 disables them
+    pub progress_interval: u64,
+}
+
+impl Default for BsgsConfig {
+    fn default() -> Self {
+        Self {
+            steps_count: 65_536,
+            stride: 1,
+            parallel: false,
+            progress_interval: 0,
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::fmt;
+
+/// Errors reported by the fallible BSGS entry points
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BsgsError {
+    /// The giant walk finished without matching a baby step
+    NotFound,
+    /// The scalar wasn't found within the largest allowed steps_count
+    OrderExceeded,
+    /// Target provably isn't a multiple of base, no search was done
+    NoSolution,
+    /// Solver parameters that make the search meaningless, e.g. a zero steps_count
+    InvalidParams,
+}
+
+impl fmt::Display for BsgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BsgsError::NotFound => write!(f, "no baby step matched the giant walk"),
+            BsgsError::OrderExceeded => write!(f, "scalar exceeds the maximum steps_count"),
+            BsgsError::NoSolution => write!(f, "target is not in the subgroup of base"),
+            BsgsError::InvalidParams => write!(f, "steps_count must be positive"),
+        }
+    }
+}
+
+impl std::error::Error for BsgsError {}
+
+/// Likely cause of a failed solve, reported by `diagnose`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FailureReason {
+    /// The scalar isn't below steps_count² for the built table
+    OutOfRange,
+    /// Base or target isn't on the curve
+    OffCurve,
+    /// Base has small order and generates too few points
+    SmallOrderBase,
+    /// Target isn't in the subgroup generated by base
+    DifferentSubgroup,
+    /// None of the cheap checks found a problem
+    Unknown,
+    /// Not a failure, the built table solves target
+    Solvable,
+}
+
+/// Errors parsing a curve point from decimal coordinate strings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParsePointError {
+    /// A coordinate isn't a decimal number
+    InvalidNumber,
+    /// A coordinate isn't below the base field modulus
+    OutOfField,
+    /// The coordinates don't satisfy the curve equation
+    OffCurve,
+}
+
+impl fmt::Display for ParsePointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsePointError::InvalidNumber => write!(f, "coordinate is not a decimal number"),
+            ParsePointError::OutOfField => write!(f, "coordinate is outside the base field"),
+            ParsePointError::OffCurve => write!(f, "point is not on the curve"),
+        }
+    }
+}
+
+impl std::error::Error for ParsePointError {}
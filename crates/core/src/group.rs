@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::BabyGiantOps;
+
+/// Minimal additive group interface, enough to run BSGS without arkworks
+/// Implement it for a hand-rolled group and solve with `GroupBabyGiant`
+pub trait Group: Clone + Eq + Hash {
+    /// The neutral element
+    fn identity() -> Self;
+
+    /// The group operation
+    fn add(&self, rhs: &Self) -> Self;
+
+    /// The inverse element
+    fn neg(&self) -> Self;
+
+    /// Computes k·self, defaults to double-and-add over `add`
+    fn mul_small(&self, k: u64) -> Self {
+        let mut result = Self::identity();
+        let mut doubling = self.clone();
+        let mut k = k;
+        while k > 0 {
+            if k & 1 == 1 {
+                result = result.add(&doubling);
+            }
+            doubling = doubling.add(&doubling);
+            k >>= 1;
+        }
+        result
+    }
+}
+
+/// BSGS over any `Group`, keying the table on the elements themselves
+#[derive(Clone, PartialEq, Eq)]
+pub struct GroupBabyGiant<G: Group> {
+    steps_count: u64,
+    baby_steps: HashMap<G, u64>,
+}
+
+impl<G: Group> GroupBabyGiant<G> {
+    pub fn new(steps_count: u64) -> Self {
+        Self {
+            steps_count,
+            baby_steps: HashMap::new(),
+        }
+    }
+
+    /// Solves target = x·base where base generates a subgroup of `subgroup_order`
+    /// elements, resizing the table to ceil(sqrt(subgroup_order)) and capping x below
+    /// the order (building block for Pohlig-Hellman over a cofactored group)
+    pub fn run_in_subgroup(&mut self, base: G, target: G, subgroup_order: u64) -> Option<u64> {
+        if subgroup_order == 0 {
+            return None;
+        }
+        let root = subgroup_order.isqrt();
+        self.steps_count = if root * root < subgroup_order {
+            root + 1
+        } else {
+            root
+        };
+        self.run(base, target).filter(|&res| res < subgroup_order)
+    }
+
+    /// Solves target = x·base when base's order isn't known, doubling the table up to
+    /// `max_steps` baby steps until x is found. A baby walk longer than the order cycles
+    /// back to the identity, leaving exactly order distinct entries, so the order is
+    /// inferred from the table size once a table reaches it, and x is reduced below it
+    /// Returns x with the order if it was detected
+    pub fn run_unknown_order(
+        &mut self,
+        base: G,
+        target: G,
+        max_steps: u64,
+    ) -> Option<(u64, Option<u64>)> {
+        let mut steps_count = self.steps_count.clamp(1, max_steps.max(1));
+        loop {
+            self.steps_count = steps_count;
+            self.baby_steps(&base);
+            let distinct = self.baby_steps.len() as u64;
+            let order = (distinct < steps_count || base.mul_small(steps_count) == G::identity())
+                .then_some(distinct);
+
+            if let Some(res) = self.solve(&base, target.clone()) {
+                return Some((order.map_or(res, |order| res % order), order));
+            }
+            // The whole subgroup was covered, target isn't in it
+            if order.is_some() || steps_count >= max_steps {
+                return None;
+            }
+            steps_count = steps_count.saturating_mul(2).min(max_steps);
+        }
+    }
+}
+
+impl<G: Group> BabyGiantOps for GroupBabyGiant<G> {
+    type El = G;
+    type Scalar = u64;
+    type Key = G;
+
+    fn steps_count(&self) -> u64 {
+        self.steps_count
+    }
+
+    fn key(&self, el: &G) -> G {
+        el.clone()
+    }
+
+    fn store_baby_step(&mut self, key: G, baby_step: u64) {
+        self.baby_steps.entry(key).or_insert(baby_step);
+    }
+
+    fn clear_baby_steps(&mut self) {
+        self.baby_steps.clear();
+    }
+
+    fn in_baby_steps(&self, target: &G) -> Option<u64> {
+        self.baby_steps.get(target).copied()
+    }
+
+    fn identity(&self) -> G {
+        G::identity()
+    }
+
+    fn el_operation(&self, lhs: &G, rhs: &G) -> G {
+        lhs.add(rhs)
+    }
+
+    fn scalar_mul(&self, el: &G, k: u64) -> G {
+        el.mul_small(k)
+    }
+
+    fn giant_step_jump(&self, base: &G) -> G {
+        base.mul_small(self.steps_count).neg()
+    }
+
+    fn process_result(&self, baby: &u64, giant: &u64) -> u64 {
+        giant * self.steps_count + baby
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Integers under addition mod 1009
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    struct AddMod(u64);
+
+    const P: u64 = 1009;
+
+    impl Group for AddMod {
+        fn identity() -> Self {
+            AddMod(0)
+        }
+
+        fn add(&self, rhs: &Self) -> Self {
+            AddMod((self.0 + rhs.0) % P)
+        }
+
+        fn neg(&self) -> Self {
+            AddMod((P - self.0) % P)
+        }
+    }
+
+    #[test]
+    fn toy_group() {
+        let mut bsgs = GroupBabyGiant::new(32);
+        let base = AddMod(7);
+        for x in [0_u64, 1, 31, 32, 500, 1008] {
+            assert_eq!(bsgs.run(base, base.mul_small(x)), Some(x));
+        }
+    }
+
+    /// Integers under addition mod 1000, with subgroups of every order dividing 1000
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    struct Cofactored(u64);
+
+    impl Group for Cofactored {
+        fn identity() -> Self {
+            Cofactored(0)
+        }
+
+        fn add(&self, rhs: &Self) -> Self {
+            Cofactored((self.0 + rhs.0) % 1000)
+        }
+
+        fn neg(&self) -> Self {
+            Cofactored((1000 - self.0) % 1000)
+        }
+    }
+
+    #[test]
+    fn run_in_small_subgroup() {
+        // 100 generates the order 10 subgroup
+        let mut bsgs = GroupBabyGiant::new(1);
+        let base = Cofactored(100);
+        for x in 0..10 {
+            assert_eq!(bsgs.run_in_subgroup(base, base.mul_small(x), 10), Some(x));
+        }
+        assert_eq!(bsgs.steps_count(), 4);
+
+        // Outside the subgroup
+        assert_eq!(bsgs.run_in_subgroup(base, Cofactored(5), 10), None);
+        assert_eq!(bsgs.run_in_subgroup(base, base, 0), None);
+    }
+
+    #[test]
+    fn run_unknown_order_infers_order() {
+        // 40 generates the order 25 subgroup, a table grown from 1 step solves at 8
+        // before reaching the order, so it stays unknown
+        let base = Cofactored(40);
+        let mut bsgs = GroupBabyGiant::new(1);
+        assert_eq!(
+            bsgs.run_unknown_order(base, base.mul_small(17), 100),
+            Some((17, None))
+        );
+        assert_eq!(bsgs.steps_count(), 8);
+        assert_eq!(
+            bsgs.run_unknown_order(base, base.mul_small(117), 100),
+            Some((17, None))
+        );
+
+        // A table past the order cycles, 117 is reduced to 17
+        let mut bsgs = GroupBabyGiant::new(32);
+        assert_eq!(
+            bsgs.run_unknown_order(base, base.mul_small(117), 100),
+            Some((17, Some(25)))
+        );
+        assert_eq!(
+            bsgs.run_unknown_order(base, base.mul_small(0), 100),
+            Some((0, Some(25)))
+        );
+        assert_eq!(bsgs.run_unknown_order(base, Cofactored(1), 100), None);
+
+        // Order 1009 is never reached by a 16 step table, x is still found below 16²
+        let mut bsgs = GroupBabyGiant::new(1);
+        let base = AddMod(7);
+        assert_eq!(
+            bsgs.run_unknown_order(base, base.mul_small(200), 16),
+            Some((200, None))
+        );
+        assert_eq!(bsgs.run_unknown_order(base, base.mul_small(1000), 16), None);
+    }
+
+    #[test]
+    fn default_mul_small() {
+        for k in [0_u64, 1, 2, 1000] {
+            assert_eq!(AddMod(3).mul_small(k), AddMod(3 * k % P));
+        }
+    }
+}
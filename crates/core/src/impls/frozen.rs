@@ -0,0 +1,123 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use crate::impls::grumpkin::FqKey;
+
+/// Average keys per displacement bucket, more is smaller but slower to build
+const BUCKET_SIZE: usize = 4;
+
+/// Immutable baby step table behind a minimal perfect hash (hash and displace)
+/// Every stored key has its own slot, so a lookup is one probe and a key compare,
+/// and the slots are exactly as many as the entries
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrozenTable {
+    /// Per bucket seed placing its keys in free slots
+    seeds: Vec<u32>,
+    slots: Vec<(FqKey, u64)>,
+}
+
+/// Folds all key words with a splitmix64 finalizer, seed 0 picks the bucket
+fn hash(key: &FqKey, seed: u64) -> u64 {
+    let mut h = seed.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    for word in key.chunks_exact(8) {
+        h ^= u64::from_le_bytes(word.try_into().unwrap());
+        h = (h ^ (h >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        h = (h ^ (h >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        h ^= h >> 31;
+    }
+    h
+}
+
+impl FrozenTable {
+    pub fn new<S>(table: &HashMap<FqKey, u64, S>) -> Self {
+        let n = table.len() as u64;
+        let bucket_count = table.len().div_ceil(BUCKET_SIZE).max(1);
+        let mut buckets = vec![Vec::new(); bucket_count];
+        for (key, &baby_step) in table {
+            buckets[(hash(key, 0) % bucket_count as u64) as usize].push((*key, baby_step));
+        }
+
+        // Largest buckets first, while most slots are still free
+        let mut order: Vec<usize> = (0..bucket_count).collect();
+        order.sort_by_key(|&b| Reverse(buckets[b].len()));
+
+        let mut seeds = vec![0; bucket_count];
+        let mut slots = vec![None; table.len()];
+        let mut placed = Vec::with_capacity(BUCKET_SIZE);
+        for b in order.into_iter().take_while(|&b| !buckets[b].is_empty()) {
+            for seed in 1_u32.. {
+                placed.clear();
+                let fits = buckets[b].iter().all(|(key, _)| {
+                    let slot = (hash(key, seed as u64) % n) as usize;
+                    let free = slots[slot].is_none() && !placed.contains(&slot);
+                    placed.push(slot);
+                    free
+                });
+                if fits {
+                    seeds[b] = seed;
+                    for (&slot, &entry) in placed.iter().zip(&buckets[b]) {
+                        slots[slot] = Some(entry);
+                    }
+                    break;
+                }
+            }
+        }
+
+        Self {
+            seeds,
+            slots: slots.into_iter().map(Option::unwrap).collect(),
+        }
+    }
+
+    /// Baby step stored under key
+    pub fn get(&self, key: &FqKey) -> Option<u64> {
+        if self.slots.is_empty() {
+            return None;
+        }
+        let seed = self.seeds[(hash(key, 0) % self.seeds.len() as u64) as usize];
+        let (stored, baby_step) =
+            &self.slots[(hash(key, seed as u64) % self.slots.len() as u64) as usize];
+        (stored == key).then_some(*baby_step)
+    }
+
+    /// Stored keys in slot order
+    pub fn keys(&self) -> impl Iterator<Item = &FqKey> {
+        self.slots.iter().map(|(key, _)| key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Bytes held by the seeds and slots
+    pub fn approx_bytes(&self) -> usize {
+        self.seeds.len() * size_of::<u32>() + self.slots.len() * size_of::<(FqKey, u64)>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::grumpkin::{fq_key, grumpkin_scalar_base};
+
+    #[test]
+    fn frozen_table_finds_every_key() {
+        let table: HashMap<FqKey, u64> = (0..500)
+            .map(|k| (fq_key(&grumpkin_scalar_base(k)), k))
+            .collect();
+        let frozen = FrozenTable::new(&table);
+
+        assert_eq!(frozen.len(), 500);
+        for (key, &k) in &table {
+            assert_eq!(frozen.get(key), Some(k));
+        }
+        assert_eq!(frozen.get(&fq_key(&grumpkin_scalar_base(500))), None);
+
+        let empty = FrozenTable::new(&HashMap::<FqKey, u64>::new());
+        assert!(empty.is_empty());
+        assert_eq!(empty.get(&[0; 32]), None);
+    }
+}
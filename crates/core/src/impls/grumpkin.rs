@@ -1,162 +1,3697 @@
-use ark_ff::BigInt;
-use ark_grumpkin::{Affine, Fq, Fr, G_GENERATOR_X, G_GENERATOR_Y};
-use std::{collections::HashMap, str::FromStr};
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{BigInt, BigInteger, Field, PrimeField, Zero};
+use ark_grumpkin::{Affine, Fq, Fr, G_GENERATOR_X, G_GENERATOR_Y, Projective};
+use ark_serialize::CanonicalSerialize;
+use sha2::{Digest, Sha256};
+use std::{
+    cell::Cell,
+    collections::{BTreeMap, HashMap, hash_map::Entry},
+    fmt,
+    hash::{BuildHasher, DefaultHasher, Hasher, RandomState},
+    io::{self, Write},
+    ops::Range,
+    str::FromStr,
+    time::Duration,
+};
 
-use crate::BabyGiantOps;
+use crate::{
+    BabyGiantOps, BsgsConfig, BsgsError, FailureReason, Group, ParsePointError,
+    impls::frozen::FrozenTable,
+};
+
+/// Baby step table key, the canonically serialized x-coordinate
+pub type FqKey = [u8; 32];
+
+/// Tables from this many baby steps on hash keys with `TableHasher::Fx`
+pub const FX_HASHER_STEPS: u64 = 1 << 16;
+
+/// Baby steps normalized to affine together, sharing one field inversion
+const BABY_STEP_BATCH: usize = 256;
+
+/// Points each thread of `baby_steps_parallel` walks before they're inserted
+#[cfg(not(target_arch = "wasm32"))]
+const PARALLEL_WINDOW: u64 = 1 << 14;
+
+/// Order of the Grumpkin group generated by `g()` (the BN254 base field modulus)
+pub const GRUMPKIN_ORDER: &str =
+    "21888242871839275222246405745257275088696311157297823662689037894645226208583";
+
+/// Grumpkin group order as a `BigInt`, the `Fr` modulus
+pub fn grumpkin_order() -> BigInt<4> {
+    Fr::MODULUS
+}
+
+/// Bit length of the Grumpkin group order
+pub fn order_bits() -> u32 {
+    Fr::MODULUS_BIT_SIZE
+}
 
 /// Grumpkin generator point
 pub fn g() -> Affine {
     Affine::new_unchecked(G_GENERATOR_X, G_GENERATOR_Y)
 }
 
+/// Computes x·g, handy for building targets with a known log
+pub fn grumpkin_scalar_base(x: u64) -> Affine {
+    (g() * Fr::from(x)).into()
+}
+
+/// Deterministically maps a seed to a Grumpkin point by try-and-increment
+/// x = SHA-256(seed || counter_le) reduced into Fq for counter = 0, 1, ... until x is on
+/// the curve, taking the smaller of the two y values. Grumpkin has prime order so no
+/// cofactor clearing is needed. Not constant time, meant for experiments only
+pub fn hash_to_grumpkin(seed: &[u8]) -> Affine {
+    (0_u64..)
+        .find_map(|counter| {
+            let digest = Sha256::new()
+                .chain_update(seed)
+                .chain_update(counter.to_le_bytes())
+                .finalize();
+            Affine::get_point_from_x_unchecked(Fq::from_le_bytes_mod_order(&digest), false)
+        })
+        .expect("about half of all x values are on the curve")
+}
+
+/// Converts a solved scalar into the Grumpkin scalar field
+pub fn scalar_to_fr(s: u64) -> Fr {
+    s.into()
+}
+
+/// Big endian bytes of a solved scalar, the standard form for hashing and FFI
+pub fn scalar_to_bytes(s: u64) -> [u8; 8] {
+    s.to_be_bytes()
+}
+
+/// Inverse of `scalar_to_bytes`, None unless given exactly 8 bytes
+pub fn scalar_from_bytes(b: &[u8]) -> Option<u64> {
+    b.try_into().ok().map(u64::from_be_bytes)
+}
+
+/// Scalar solved on Grumpkin, a newtype so it can't be mixed up with other curves' scalars
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GrumpkinScalar(pub u64);
+
+impl From<GrumpkinScalar> for u64 {
+    fn from(s: GrumpkinScalar) -> u64 {
+        s.0
+    }
+}
+
+impl From<GrumpkinScalar> for Fr {
+    fn from(s: GrumpkinScalar) -> Fr {
+        scalar_to_fr(s.0)
+    }
+}
+
+/// Table key for a point, decoupled from arkworks' internal `Fq` hashing
+pub fn fq_key(p: &Affine) -> FqKey {
+    let mut key = [0; 32];
+    p.x.serialize_compressed(&mut key[..])
+        .expect("Fq serializes to 32 bytes");
+    key
+}
+
+/// Table key for a point carrying the parity of y in the spare top bit
+/// Fq fits in 254 bits, so P and -P get distinct keys without storing y
+pub fn fq_parity_key(p: &Affine) -> FqKey {
+    let mut key = fq_key(p);
+    if p.y.into_bigint().is_odd() {
+        key[31] |= 0x80;
+    }
+    key
+}
+
+/// Table key for a canonical point under key_mode
+fn mode_key(key_mode: KeyMode, p: &Affine) -> FqKey {
+    match key_mode {
+        KeyMode::X => fq_key(p),
+        KeyMode::XParity => fq_parity_key(p),
+    }
+}
+
+/// x-coordinate stored in a table key of either mode
+pub fn key_to_fq(key: &FqKey) -> Fq {
+    let mut x = *key;
+    x[31] &= 0x7f;
+    Fq::from_le_bytes_mod_order(&x)
+}
+
+/// What the baby step table keys on
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyMode {
+    /// x-coordinate only, P and -P share a key
+    #[default]
+    X,
+    /// x-coordinate and y parity, P and -P are distinct
+    XParity,
+}
+
+/// Direction of the giant walk
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    /// Table of k·base, walks the target down by m·base
+    #[default]
+    Forward,
+    /// Table of k·m·base, walks the target down by base
+    Backward,
+}
+
+/// Point on y² = x³ + ISO_B, isomorphic to Grumpkin's y² = x³ - 17
+/// via (x, y) ↦ (u²·x, u³·y) with u = ISO_U, so ISO_B = -17·u⁶
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IsoAffine {
+    pub x: Fq,
+    pub y: Fq,
+}
+
+/// Scaling factor u of the isomorphism
+pub const ISO_U: u64 = 2;
+/// Curve coefficient of the isomorphic model, -17·2⁶
+pub const ISO_B: i64 = -1088;
+
+/// Maps a Grumpkin point into the isomorphic model, None for the identity
+pub fn to_isomorphic(p: &Affine) -> Option<IsoAffine> {
+    let u = Fq::from(ISO_U);
+    let (x, y) = p.xy()?;
+    Some(IsoAffine {
+        x: x * u.square(),
+        y: y * u.square() * u,
+    })
+}
+
+/// Maps a point of the isomorphic model back onto Grumpkin
+/// Panics if the point isn't on y² = x³ + ISO_B
+pub fn from_isomorphic(p: IsoAffine) -> Affine {
+    let u_inv = Fq::from(ISO_U).inverse().expect("u is non zero");
+    Affine::new(p.x * u_inv.square(), p.y * u_inv.square() * u_inv)
+}
+
+/// Hasher of the baby step table, both are unkeyed so iteration order is reproducible
+/// Keys are curve x-coordinates an attacker can't aim at buckets, so the table
+/// doesn't need SipHash's flooding resistance once it's large enough for speed to matter
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TableHasher {
+    /// std's SipHash-1-3
+    #[default]
+    Sip,
+    /// FxHash-style multiply and rotate over 8 byte words
+    Fx,
+}
+
+impl BuildHasher for TableHasher {
+    type Hasher = KeyHasher;
+
+    fn build_hasher(&self) -> KeyHasher {
+        match self {
+            TableHasher::Sip => KeyHasher::Sip(DefaultHasher::new()),
+            TableHasher::Fx => KeyHasher::Fx(0),
+        }
+    }
+}
+
+/// Hasher state built by `TableHasher`
+#[derive(Clone, Debug)]
+pub enum KeyHasher {
+    Sip(DefaultHasher),
+    Fx(u64),
+}
+
+impl Hasher for KeyHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        match self {
+            KeyHasher::Sip(hasher) => hasher.write(bytes),
+            KeyHasher::Fx(hash) => {
+                for chunk in bytes.chunks(8) {
+                    let mut word = [0; 8];
+                    word[..chunk.len()].copy_from_slice(chunk);
+                    *hash = (hash.rotate_left(5) ^ u64::from_le_bytes(word))
+                        .wrapping_mul(0x517c_c1b7_2722_0a95);
+                }
+            }
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        match self {
+            KeyHasher::Sip(hasher) => hasher.finish(),
+            KeyHasher::Fx(hash) => *hash,
+        }
+    }
+}
+
+/// What `baby_steps` does when two indices map to the same key
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CollisionPolicy {
+    /// Keep the index inserted first
+    KeepFirst,
+    /// Keep the smaller index
+    #[default]
+    KeepSmallest,
+    /// Keep both, lookups recompute the candidates to pick the matching point
+    StoreBoth,
+}
+
+/// Yields `(n, target + n·jump)` for n in 0..steps_count, the points of a giant walk
+/// Accumulates in projective coordinates, normalizing each point once as it's yielded
+#[derive(Clone, Debug)]
+pub struct GiantStepIterator {
+    acc: Projective,
+    jump: Affine,
+    giant_step: u64,
+    steps_count: u64,
+}
+
+impl GiantStepIterator {
+    pub fn new(target: Affine, jump: Affine, steps_count: u64) -> Self {
+        Self {
+            acc: target.into(),
+            jump,
+            giant_step: 0,
+            steps_count,
+        }
+    }
+}
+
+impl Iterator for GiantStepIterator {
+    type Item = (u64, Affine);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.giant_step >= self.steps_count {
+            return None;
+        }
+        // Advance lazily so no addition is wasted past the last point
+        if self.giant_step > 0 {
+            self.acc += self.jump;
+        }
+        let item = (self.giant_step, self.acc.into());
+        self.giant_step += 1;
+        Some(item)
+    }
+}
+
+/// Progress of the giant walk passed to the `run_with_progress` callback
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RunProgress {
+    /// Giant steps done so far
+    pub giant_step: u64,
+    /// Total giant steps of the walk
+    pub steps_count: u64,
+    /// Time since the run started, None without the `timer` feature
+    pub elapsed: Option<Duration>,
+}
+
+/// Algorithm `solve_within_time` ended its last solve with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolveMode {
+    /// Found by the giant walk over the table
+    Bsgs,
+    /// Fell back to `kangaroo` after the walk missed or ran out of time
+    Kangaroo,
+}
+
+/// Evidence for target = (giant_index·steps_count + baby_index)·base, see `verify_certificate`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SolveCertificate {
+    pub baby_index: u64,
+    pub giant_index: u64,
+    /// Table size the indices refer to
+    pub steps_count: u64,
+    /// Where the walks meet, baby_index·base = target - giant_index·steps_count·base
+    pub meeting_point: Affine,
+}
+
+/// Checks a `SolveCertificate` with a few scalar multiplications, without solving
+pub fn verify_certificate(base: &Affine, target: &Affine, cert: &SolveCertificate) -> bool {
+    let giant_jump = *base * Fr::from(cert.steps_count) * Fr::from(cert.giant_index);
+    cert.baby_index < cert.steps_count
+        && *base * Fr::from(cert.baby_index) == cert.meeting_point
+        && *target - giant_jump == cert.meeting_point
+}
+
+/// Resumable state of an interrupted giant walk
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// Element reached by the walk, target + giant_step·jump
+    pub current: Affine,
+    /// Next giant step index to check
+    pub giant_step: u64,
+}
+
+/// Mutable view of a baby step table, the whole table or one shard of a parallel build
+struct TableShard<'a> {
+    baby_steps: &'a mut HashMap<FqKey, u64, TableHasher>,
+    collided: &'a mut HashMap<FqKey, Vec<u64>>,
+    x_collisions: &'a mut usize,
+}
+
+impl TableShard<'_> {
+    /// Inserts a baby step under policy, returns the index already stored
+    fn insert(&mut self, policy: CollisionPolicy, key: FqKey, baby_step: u64) -> Option<u64> {
+        match self.baby_steps.entry(key) {
+            Entry::Vacant(entry) => {
+                entry.insert(baby_step);
+                None
+            }
+            Entry::Occupied(mut entry) => {
+                let prev = *entry.get();
+                match policy {
+                    CollisionPolicy::KeepFirst => {}
+                    CollisionPolicy::KeepSmallest => {
+                        if baby_step < prev {
+                            entry.insert(baby_step);
+                        }
+                    }
+                    CollisionPolicy::StoreBoth => {
+                        self.collided.entry(key).or_default().push(baby_step)
+                    }
+                }
+                Some(prev)
+            }
+        }
+    }
+
+    /// Inserts current = baby_step·step, counting collisions of distinct points
+    fn insert_walked(
+        &mut self,
+        policy: CollisionPolicy,
+        step: &Affine,
+        key: FqKey,
+        current: &Affine,
+        baby_step: u64,
+    ) {
+        if let Some(prev) = self.insert(policy, key, baby_step) {
+            // Recompute the overwritten point, only distinct points are collisions
+            if Group::mul_small(step, prev) != *current {
+                *self.x_collisions += 1;
+            }
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct GrumpkinBabyGiant {
     steps_count: u64,
-    baby_steps: HashMap<Fq, u64>,
+    baby_steps: HashMap<FqKey, u64, TableHasher>,
+    last_giant_steps: Option<u64>,
+    direction: Direction,
+    bidirectional: bool,
+    descending: bool,
+    verify_results: bool,
+    key_mode: KeyMode,
+    stride: u64,
+    parallel: bool,
+    progress_interval: u64,
+    table_base: Option<Affine>,
+    table_step: Affine,
+    /// steps_count the current table was built with
+    table_steps_count: Option<u64>,
+    result_cache: Option<HashMap<(Affine, Affine), u64>>,
+    /// (base, target, result) of the last successful `run`, chained by `run_next`
+    last_solved: Option<(Affine, Affine, u64)>,
+    cache_hits: u64,
+    x_collisions: usize,
+    collision_policy: CollisionPolicy,
+    /// Extra indices kept under `CollisionPolicy::StoreBoth`
+    collided: HashMap<FqKey, Vec<u64>>,
+    /// Giant indices in the order they're tried, None for `0..steps_count`
+    step_schedule: Option<Vec<u64>>,
+    giant_lookups: Cell<u64>,
+    /// Table moved behind a perfect hash by `freeze`
+    frozen: Option<FrozenTable>,
+    last_solve_mode: Option<SolveMode>,
+}
+
+/// Prints the table size instead of the whole table
+impl fmt::Debug for GrumpkinBabyGiant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GrumpkinBabyGiant")
+            .field("steps_count", &self.steps_count)
+            .field("baby_steps_len", &self.table_len())
+            .finish_non_exhaustive()
+    }
 }
 
 impl GrumpkinBabyGiant {
+    /// Panics if steps_count is 0, see `try_new`
+    /// Hashes the table with SipHash below `FX_HASHER_STEPS` steps and Fx from there on
     pub fn new(steps_count: u64) -> Self {
+        assert!(steps_count > 0, "steps_count must be positive");
+        let hasher = if steps_count >= FX_HASHER_STEPS {
+            TableHasher::Fx
+        } else {
+            TableHasher::Sip
+        };
         Self {
             steps_count,
-            baby_steps: HashMap::new(),
+            baby_steps: HashMap::with_hasher(hasher),
+            last_giant_steps: None,
+            direction: Direction::Forward,
+            bidirectional: false,
+            descending: false,
+            verify_results: true,
+            key_mode: KeyMode::X,
+            stride: 1,
+            parallel: false,
+            progress_interval: 0,
+            table_base: None,
+            table_step: Affine::identity(),
+            table_steps_count: None,
+            result_cache: None,
+            last_solved: None,
+            cache_hits: 0,
+            x_collisions: 0,
+            collision_policy: CollisionPolicy::default(),
+            collided: HashMap::new(),
+            step_schedule: None,
+            giant_lookups: Cell::new(0),
+            frozen: None,
+            last_solve_mode: None,
         }
     }
-    pub fn get_baby_steps(&self) -> &HashMap<Fq, u64> {
-        &self.baby_steps
+
+    /// Fails with `InvalidParams` for a zero steps_count, which can't solve anything
+    pub fn try_new(steps_count: u64) -> Result<Self, BsgsError> {
+        if steps_count == 0 {
+            return Err(BsgsError::InvalidParams);
+        }
+        Ok(Self::new(steps_count))
     }
-}
 
-/// Implementation for u128 modular exponentiation
-impl BabyGiantOps for GrumpkinBabyGiant {
-    type El = Affine;
-    type Scalar = u64;
+    /// Builds a solver from a `BsgsConfig`
+    pub fn from_config(config: BsgsConfig) -> Self {
+        let mut grumpy_bsgs = Self::new(config.steps_count).with_stride(config.stride);
+        grumpy_bsgs.parallel = config.parallel;
+        grumpy_bsgs.progress_interval = config.progress_interval;
+        grumpy_bsgs
+    }
 
-    fn steps_count(&self) -> Self::Scalar {
-        self.steps_count
+    /// Current options as a `BsgsConfig`
+    pub fn config(&self) -> BsgsConfig {
+        BsgsConfig {
+            steps_count: self.steps_count,
+            stride: self.stride,
+            parallel: self.parallel,
+            progress_interval: self.progress_interval,
+        }
     }
 
-    fn baby_steps(&mut self, base: &Self::El) {
-        let mut current = *base;
+    /// Builds the baby steps for base on another thread, joining returns the solver
+    /// ready for `solve`, leaving the caller free to do other work meanwhile
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn build_async(mut self, base: Affine) -> std::thread::JoinHandle<Self> {
+        std::thread::spawn(move || {
+            self.baby_steps(&base);
+            self
+        })
+    }
+
+    /// Giant steps between `run_with_progress` reports, 0 disables them
+    pub fn with_progress_interval(mut self, progress_interval: u64) -> Self {
+        self.progress_interval = progress_interval;
+        self
+    }
+
+    /// Sets what the table keys on, see `KeyMode`
+    pub fn with_key_mode(mut self, key_mode: KeyMode) -> Self {
+        self.key_mode = key_mode;
+        self
+    }
+
+    /// Stores only every `stride`-th baby step, dividing table memory by `stride`
+    /// Each lookup then also checks up to `stride - 1` points below the element
+    pub fn with_stride(mut self, stride: u64) -> Self {
+        self.stride = stride.max(1);
+        self
+    }
+
+    /// Tries the giant indices in `step_schedule` order instead of `0..steps_count`
+    /// Front-loading the giant steps covering likely scalars (timestamps, counters)
+    /// cuts the expected walk, indices left out of the schedule are never tried
+    pub fn with_step_schedule(mut self, step_schedule: Vec<u64>) -> Self {
+        self.step_schedule = Some(step_schedule);
+        self
+    }
+
+    /// Overrides the hasher `new` picked from steps_count, dropping any built table
+    pub fn with_hasher(mut self, hasher: TableHasher) -> Self {
+        self.baby_steps = HashMap::with_hasher(hasher);
+        self.frozen = None;
+        self
+    }
+
+    /// Hasher of the baby step table
+    pub fn hasher(&self) -> TableHasher {
+        *self.baby_steps.hasher()
+    }
 
-        let mut baby_step = 0;
-        while baby_step < self.steps_count {
-            baby_step += 1;
-            self.baby_steps.insert(current.x, baby_step);
-            current = (current + base).into();
+    /// Sets how key collisions in the table are resolved, see `CollisionPolicy`
+    pub fn with_collision_policy(mut self, collision_policy: CollisionPolicy) -> Self {
+        self.collision_policy = collision_policy;
+        self
+    }
+
+    /// Inserts a baby step under the `CollisionPolicy`, returns the index already stored
+    fn insert_baby_step(&mut self, key: FqKey, baby_step: u64) -> Option<u64> {
+        let policy = self.collision_policy;
+        self.table_shard().insert(policy, key, baby_step)
+    }
+
+    /// The whole table viewed as a single shard
+    fn table_shard(&mut self) -> TableShard<'_> {
+        TableShard {
+            baby_steps: &mut self.baby_steps,
+            collided: &mut self.collided,
+            x_collisions: &mut self.x_collisions,
         }
     }
 
-    fn el_operation(&self, lhs: &Self::El, rhs: &Self::El) -> Self::El {
-        (*lhs + *rhs).into()
+    /// Enables caching `run` results keyed by (base, target) points
+    /// Repeated solves of the same target skip the table build and giant walk
+    pub fn with_result_cache(mut self) -> Self {
+        self.result_cache = Some(HashMap::new());
+        self
     }
 
-    fn giant_step_jump(&self, base: &Self::El) -> Self::El {
-        let m: Fr = self.steps_count.into();
-        (-(*base * m)).into()
+    /// Number of `run` calls answered from the result cache
+    pub fn cache_hits(&self) -> u64 {
+        self.cache_hits
     }
 
-    fn process_result(&self, baby: &u64, giant: &u64) -> u64 {
-        let step_count = self.steps_count;
-        giant * step_count + baby
+    /// Sets the giant walk direction, both recover the same scalars
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
     }
 
-    fn in_baby_steps(&self, target: &Self::El) -> Option<&Self::Scalar> {
-        self.baby_steps.get(&target.x)
+    /// Checks giant steps from both ends toward the middle, 0, m - 1, 1, m - 2, ...
+    /// so scalars near the top of the range are found as fast as small ones
+    pub fn with_bidirectional(mut self, bidirectional: bool) -> Self {
+        self.bidirectional = bidirectional;
+        self
     }
-}
 
-pub fn grumpkin_bsgs(target: Affine, size: u64) -> u64 {
-    let mut grumpy_bsgs = GrumpkinBabyGiant::new(size);
+    /// Builds the table from the top index down, subtracting base each step
+    /// Entries are the same k·base to k either way, results don't change
+    pub fn with_descending(mut self, descending: bool) -> Self {
+        self.descending = descending;
+        self
+    }
+
+    /// Recomputes each match before accepting it, on by default. Skipping it saves a
+    /// scalar multiplication per solve, but then an x-only key matching -target, or a
+    /// table built for another base, yields a wrong scalar instead of walking on
+    pub fn with_verify_results(mut self, verify_results: bool) -> Self {
+        self.verify_results = verify_results;
+        self
+    }
+
+    /// Giant walk for `with_bidirectional`, one walk up from the target and one down
+    /// from its last giant step. x-only keys also match the negated point one giant
+    /// step above the true one, which the downward walk meets first, so matches are verified
+    fn solve_steps_bidirectional(&self, base: &Affine, target: Affine) -> Option<(u64, u64)> {
+        let jump = self.giant_step_jump(base);
+        let m = self.steps_count;
+        let top = (target + jump * Fr::from(m - 1)).into();
 
-    let res = grumpy_bsgs.run(g(), target.into());
+        let mut up = GiantStepIterator::new(target, jump, m.div_ceil(2));
+        let mut down = GiantStepIterator::new(top, -jump, m / 2).map(|(n, p)| (m - 1 - n, p));
+        let verified = |(giant_step, current): (u64, Affine)| {
+            self.giant_lookups.set(self.giant_lookups.get() + 1);
+            let baby_step = self.in_baby_steps(&current)?;
+            let res = self.process_result(&baby_step, &giant_step);
+            (self.scalar_mul(base, res) == target).then_some((baby_step, giant_step))
+        };
 
-    match res {
-        Some(res) => res,
-        None => 0,
+        loop {
+            let (low, high) = (up.next(), down.next());
+            if low.is_none() && high.is_none() {
+                return None;
+            }
+            if let Some(found) = low.and_then(verified).or_else(|| high.and_then(verified)) {
+                return Some(found);
+            }
+        }
     }
-}
 
-pub fn grumpkin_bsgs_32(target: Affine) -> u64 {
-    grumpkin_bsgs(target, 65_536)
-}
+    /// Giant steps looked up by the last walk, its actual cost whatever the schedule
+    pub fn last_giant_lookups(&self) -> u64 {
+        self.giant_lookups.get()
+    }
 
-pub fn grumpkin_bsgs_40(target: Affine) -> u64 {
-    grumpkin_bsgs(target, 1_048_576)
-}
+    /// Giant step index at which the last `run` matched, None if it failed
+    /// Values close to `steps_count` mean the table is undersized for the scalar
+    pub fn last_giant_steps(&self) -> Option<u64> {
+        self.last_giant_steps
+    }
 
-pub fn grumpkin_str_to_point(x: &str, y: &str) -> Affine {
-    Affine::new_unchecked(
-        Fq::new(BigInt::from_str(x).unwrap()),
-        Fq::new(BigInt::from_str(y).unwrap()),
-    )
-}
+    /// Fast path for a target that is itself in the table, skipping the giant walk
+    /// x-only keys also match -target, so the index is checked against the table base
+    /// None without a table or when the scalar isn't below the table's range
+    pub fn lookup_direct(&self, target: &Affine) -> Option<u64> {
+        let base = self.table_base?;
+        let res = self.process_result(&self.in_baby_steps(target)?, &0);
+        (self.scalar_mul(&base, res) == *target).then_some(res)
+    }
 
-#[cfg(test)]
-mod tests {
-    use std::time::Instant;
+    /// The giant step jump for base, -(steps_count·base) in the `Forward` direction
+    /// Recomputed from the current steps_count, so it changes whenever steps_count does
+    pub fn compute_jump(&self, base: &Affine) -> Affine {
+        self.giant_step_jump(base)
+    }
 
-    use ark_grumpkin::Fr;
+    /// Applies one giant step to `current` and looks the result up in the table
+    /// Returns the next point and its baby step if it hit one, for stepping through by hand
+    /// Panics if no table has been built
+    pub fn giant_step_probe(&self, current: &Affine) -> (Affine, Option<u64>) {
+        let base = self
+            .table_base
+            .expect("build the baby steps before probing");
+        let next = self.el_operation(current, &self.giant_step_jump(&base));
+        (next, self.in_baby_steps(&next))
+    }
 
-    use crate::{
-        impls::grumpkin::{g, GrumpkinBabyGiant},
-        BabyGiantOps,
-    };
+    /// Splits a scalar into the `(giant, baby)` steps where the walk meets the table,
+    /// the inverse of `process_result` for scalars below steps_count²
+    pub fn decompose_scalar(&self, scalar: u64) -> (u64, u64) {
+        let (high, low) = (scalar / self.steps_count, scalar % self.steps_count);
+        match self.direction {
+            Direction::Forward => (high, low),
+            Direction::Backward => (low, high),
+        }
+    }
 
-    #[test]
-    fn grumpkin_bsgs_40() {
-        let mut grumpy_bsgs = GrumpkinBabyGiant::new(1_048_576);
+    /// Average giant steps to solve a scalar drawn uniformly below `max_scalar`
+    /// Half of the max_scalar / steps_count giant steps the range spans
+    pub fn expected_giant_steps(&self, max_scalar: u64) -> f64 {
+        max_scalar as f64 / (2 * self.steps_count) as f64
+    }
 
-        let x_num = 840368900803_u64;
-        let x: Fr = x_num.into();
-        let target = (g() * x).into();
+    /// Largest scalar `run` recovers, steps_count² - 1 as the walk takes steps_count
+    /// giant steps, saturating at u64::MAX
+    pub fn max_solvable(&self) -> u64 {
+        self.steps_count
+            .checked_mul(self.steps_count)
+            .map_or(u64::MAX, |covered| covered - 1)
+    }
 
-        let now = Instant::now();
+    /// Scalars in range that `run` can't recover with the current steps_count, the
+    /// tail of range past `max_solvable`, as a range so wide ones cost nothing
+    pub fn unreachable_scalars(&self, range: Range<u64>) -> Range<u64> {
+        let first = range.start.max(self.max_solvable().saturating_add(1));
+        first.min(range.end)..range.end
+    }
 
-        let res = grumpy_bsgs.run(g(), target);
+    pub fn get_baby_steps(&self) -> &HashMap<FqKey, u64, TableHasher> {
+        &self.baby_steps
+    }
 
-        println!("Result: {:?}", res);
-        println!("\n\nGrumpkin BSGS took: {:.2?}", now.elapsed());
+    /// The table as contiguous key || little endian index records sorted by key, for
+    /// binary search on a GPU or other device. Returns the bytes and the record size
+    /// Covers the HashMap, so call it before `freeze`
+    pub fn gpu_table(&self) -> (Vec<u8>, usize) {
+        const RECORD_SIZE: usize = size_of::<FqKey>() + size_of::<u64>();
 
-        assert!(res.unwrap() == x_num, "Incorrect result");
+        let mut entries: Vec<_> = self.baby_steps.iter().collect();
+        entries.sort_unstable();
+
+        let mut bytes = Vec::with_capacity(entries.len() * RECORD_SIZE);
+        for (key, baby_step) in entries {
+            bytes.extend_from_slice(key);
+            bytes.extend_from_slice(&baby_step.to_le_bytes());
+        }
+        (bytes, RECORD_SIZE)
     }
 
-    #[test]
-    fn grumpkin_bsgs_32() {
-        let mut grumpy_bsgs = GrumpkinBabyGiant::new(65536);
+    /// x-coordinates of every table entry sorted by their canonical serialized bytes,
+    /// for external indexes or Bloom filters. Frozen entries included
+    pub fn sorted_keys(&self) -> Vec<Fq> {
+        let frozen = self.frozen.iter().flat_map(FrozenTable::keys);
+        let mut keys: Vec<FqKey> = frozen
+            .chain(self.baby_steps.keys())
+            .map(|key| {
+                // Drop the y parity bit, leaving the x bytes
+                let mut x = *key;
+                x[31] &= 0x7f;
+                x
+            })
+            .collect();
+        keys.sort_unstable();
+        keys.iter().map(key_to_fq).collect()
+    }
 
-        let x_num = 4294967295_u64;
-        let x: Fr = x_num.into();
-        let target = (g() * x).into();
+    /// Adds only the baby steps with indices in `[start, end)` to the table
+    /// Lets workers each build a slice of one table, to be combined with `merge`
+    pub fn baby_steps_range(&mut self, base: &Affine, start: u64, end: u64) {
+        let step = self.start_table(base);
 
-        let now = Instant::now();
+        // Walk covers the multiples of stride in [start, end), entry k maps k·step
+        let first = start.next_multiple_of(self.stride);
+        let count = end.saturating_sub(first).div_ceil(self.stride);
+        let mut stride_step: Projective = self.scalar_mul(&step, self.stride).into();
+        let mut baby_step = first;
+        if self.descending && count > 0 {
+            // Starts at the last multiple below end and walks down
+            baby_step = first + (count - 1) * self.stride;
+            stride_step = -stride_step;
+        }
+        let mut acc: Projective = self.scalar_mul(&step, baby_step).into();
 
-        let res = grumpy_bsgs.run(g(), target);
+        // Adds stay projective, each batch is normalized with a single inversion
+        let mut batch = Vec::with_capacity(BABY_STEP_BATCH);
+        let mut remaining = count;
+        while remaining > 0 {
+            batch.clear();
+            for _ in 0..remaining.min(BABY_STEP_BATCH as u64) {
+                batch.push(acc);
+                acc += stride_step;
+            }
+            remaining -= batch.len() as u64;
 
-        println!("Result: {:?}", res);
-        println!("\n\nGrumpkin BSGS took: {:.2?}", now.elapsed());
+            for current in Projective::normalize_batch(&batch) {
+                self.insert_walked(&step, &current, baby_step);
+                baby_step = if self.descending {
+                    baby_step.saturating_sub(self.stride)
+                } else {
+                    baby_step + self.stride
+                };
+            }
+        }
+    }
 
-        assert!(res.unwrap() == x_num, "Incorrect result");
+    /// Records base as the table's base, returning the point the table holds multiples of
+    /// Backward direction tabulates the giant multiples instead
+    fn start_table(&mut self, base: &Affine) -> Affine {
+        let step = match self.direction {
+            Direction::Forward => *base,
+            Direction::Backward => self.scalar_mul(base, self.steps_count),
+        };
+        self.table_base = Some(*base);
+        self.table_step = step;
+        self.table_steps_count = Some(self.steps_count);
+        step
     }
 
-    // #[test]
-    // pub fn grumpkin_bsgs_str() {
-    //     let pt_coords = (
-    //         "18404411293574529506939754020345889193409751730106812839425939610401353525304",
-    //         "18142326956230734014841872757503391093517090171806600353970481267506310918723",
-    //     );
+    /// Inserts current = baby_step·step under the `CollisionPolicy`, counting collisions
+    fn insert_walked(&mut self, step: &Affine, current: &Affine, baby_step: u64) {
+        let (key, policy) = (self.key(current), self.collision_policy);
+        self.table_shard()
+            .insert_walked(policy, step, key, current, baby_step);
+    }
 
-    //     let r = super::grumpkin_bsgs_str(pt_coords.0, pt_coords.1);
+    /// Builds the same table as `baby_steps` with the work spread over all cores
+    /// Threads walk contiguous slices of the points and route each key to the shard of
+    /// its low byte. Each shard then fills its own HashMap on its own thread, applying
+    /// the `CollisionPolicy` in the serial walk's order, so keys never contend across
+    /// threads and the shards, disjoint by key, are concatenated at the end
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn baby_steps_parallel(&mut self, base: &Affine) {
+        let threads = std::thread::available_parallelism().map_or(1, usize::from);
+        self.baby_steps_sharded(base, threads, PARALLEL_WINDOW);
+    }
 
-    //     assert!(r == 35235);
-    // }
+    /// `baby_steps_parallel` over `shards` threads and shard maps, each thread walking
+    /// `window` points at a time to bound the points held before insertion
+    #[cfg(not(target_arch = "wasm32"))]
+    fn baby_steps_sharded(&mut self, base: &Affine, shards: usize, window: u64) {
+        self.clear_baby_steps();
+        let step = self.start_table(base);
 
-    #[test]
-    pub fn grumpkin_baby_steps() {
-        let mut grumpy_bsgs = GrumpkinBabyGiant::new(32);
+        let (stride, descending, key_mode) = (self.stride, self.descending, self.key_mode);
+        let (policy, hasher) = (self.collision_policy, self.hasher());
+        let count = self.steps_count.div_ceil(stride);
+        // Baby step of the i-th inserted point, the serial walk's order
+        let baby_step = move |i: u64| {
+            if descending {
+                (count - 1 - i) * stride
+            } else {
+                i * stride
+            }
+        };
+        let mut stride_step: Projective = Group::mul_small(&step, stride).into();
+        if descending {
+            stride_step = -stride_step;
+        }
+
+        // Points first..last of the walk as (key, baby step, point) in their key's shard
+        let walk = move |first: u64, last: u64| {
+            let mut routed = vec![Vec::new(); shards];
+            if first == last {
+                return routed;
+            }
+            let mut acc: Projective = Group::mul_small(&step, baby_step(first)).into();
+            let mut batch = Vec::with_capacity(BABY_STEP_BATCH);
+            let mut i = first;
+            while i < last {
+                batch.clear();
+                for _ in 0..(last - i).min(BABY_STEP_BATCH as u64) {
+                    batch.push(acc);
+                    acc += stride_step;
+                }
+                for current in Projective::normalize_batch(&batch) {
+                    let current = if current.infinity {
+                        Affine::identity()
+                    } else {
+                        current
+                    };
+                    let key = mode_key(key_mode, &current);
+                    routed[key[0] as usize % shards].push((key, baby_step(i), current));
+                    i += 1;
+                }
+            }
+            routed
+        };
+
+        let mut tables: Vec<_> = (0..shards)
+            .map(|_| (HashMap::with_hasher(hasher), HashMap::new(), 0))
+            .collect();
+        let window = shards as u64 * window;
+        let mut done = 0;
+        while done < count {
+            let len = window.min(count - done);
+            let per_thread = len.div_ceil(shards as u64);
+            std::thread::scope(|scope| {
+                let walks: Vec<_> = (0..shards as u64)
+                    .map(|t| {
+                        let first = done + t * per_thread;
+                        (first.min(done + len), (first + per_thread).min(done + len))
+                    })
+                    .map(|(first, last)| scope.spawn(move || walk(first, last)))
+                    .collect();
+                let mut by_shard = vec![Vec::new(); shards];
+                for walk in walks {
+                    for (shard, entries) in walk.join().unwrap().into_iter().enumerate() {
+                        by_shard[shard].push(entries);
+                    }
+                }
+
+                // Slices come in walk order, so each shard inserts in the serial order
+                for ((baby_steps, collided, x_collisions), slices) in
+                    tables.iter_mut().zip(by_shard)
+                {
+                    scope.spawn(move || {
+                        let mut shard = TableShard {
+                            baby_steps,
+                            collided,
+                            x_collisions,
+                        };
+                        for (key, baby_step, current) in slices.into_iter().flatten() {
+                            shard.insert_walked(policy, &step, key, &current, baby_step);
+                        }
+                    });
+                }
+            });
+            done += len;
+        }
+
+        for (baby_steps, collided, x_collisions) in tables {
+            if self.baby_steps.is_empty() {
+                self.baby_steps = baby_steps;
+            } else {
+                self.baby_steps.extend(baby_steps);
+            }
+            self.collided.extend(collided);
+            self.x_collisions += x_collisions;
+        }
+        self.baby_steps.shrink_to_fit();
+    }
+
+    /// Explains why solving target against base fails, cheap checks first
+    /// With a table built for base the last check is a full giant walk, as costly as
+    /// `solve`, giving `OutOfRange` on a miss and `Solvable` on a hit. Without one the
+    /// last resort is `Unknown`. Grumpkin has prime order, so only the identity is a
+    /// `SmallOrderBase` and `DifferentSubgroup` can't happen for points on the curve
+    pub fn diagnose(&self, base: &Affine, target: &Affine) -> FailureReason {
+        if !base.is_on_curve() || !target.is_on_curve() {
+            return FailureReason::OffCurve;
+        }
+        if base.is_zero() {
+            return FailureReason::SmallOrderBase;
+        }
+        if !target.is_in_correct_subgroup_assuming_on_curve() || !self.same_subgroup(base, target) {
+            return FailureReason::DifferentSubgroup;
+        }
+        if !self.table_built_for(base) {
+            return FailureReason::Unknown;
+        }
+        match self.solve(base, *target) {
+            Some(_) => FailureReason::Solvable,
+            None => FailureReason::OutOfRange,
+        }
+    }
+
+    /// Grows the table built for base to `steps_count` entries, computing only the new ones
+    /// Rebuilds from scratch for another base, a smaller size or the `Backward` direction,
+    /// whose entries depend on steps_count
+    pub fn grow_baby_steps(&mut self, base: &Affine, steps_count: u64) {
+        let built = self.table_steps_count.unwrap_or(0);
+        let grows = self.table_base == Some(*base)
+            && self.direction == Direction::Forward
+            && built <= steps_count;
+
+        self.steps_count = steps_count;
+        if grows {
+            self.baby_steps_range(base, built, steps_count);
+        } else {
+            self.baby_steps(base);
+        }
+    }
+
+    /// Solves each `(target, steps_count)` request with a table of its own size
+    /// Requests are handled smallest first so one table is grown instead of rebuilt,
+    /// results are in request order
+    pub fn run_sorted_sizes(
+        &mut self,
+        base: Affine,
+        requests: &[(Affine, u64)],
+    ) -> Vec<Option<u64>> {
+        let mut order: Vec<usize> = (0..requests.len()).collect();
+        order.sort_by_key(|&i| requests[i].1);
+
+        let mut results = vec![None; requests.len()];
+        for i in order {
+            let (target, steps_count) = requests[i];
+            if steps_count == 0 {
+                continue;
+            }
+            self.grow_baby_steps(&base, steps_count);
+            results[i] = self.solve(&base, target);
+        }
+        results
+    }
+
+    /// Adds the entries of a partial table built for the same base and steps_count
+    pub fn merge(&mut self, other: &Self) {
+        assert_eq!(self.steps_count, other.steps_count, "steps_count mismatch");
+        assert_eq!(
+            self.table_base, other.table_base,
+            "tables built for different bases"
+        );
+
+        for (key, &baby_step) in &other.baby_steps {
+            self.insert_baby_step(*key, baby_step);
+        }
+        for (key, extra) in &other.collided {
+            for &baby_step in extra {
+                self.insert_baby_step(*key, baby_step);
+            }
+        }
+        self.x_collisions += other.x_collisions;
+    }
+
+    /// Inverse of the table, index to x-coordinate sorted by index
+    /// Extra indices kept by `CollisionPolicy::StoreBoth` are included
+    pub fn baby_steps_by_index(&self) -> BTreeMap<u64, Fq> {
+        let stored = self.baby_steps.iter().map(|(key, &k)| (k, key));
+        let extra = self
+            .collided
+            .iter()
+            .flat_map(|(key, ks)| ks.iter().map(move |&k| (k, key)));
+        stored
+            .chain(extra)
+            .map(|(k, key)| (k, key_to_fq(key)))
+            .collect()
+    }
+
+    /// Number of distinct points whose x-coordinate collided during the last build
+    /// Only P and -P share an x, so this stays 0 unless steps_count nears the group order
+    pub fn x_collisions(&self) -> usize {
+        self.x_collisions
+    }
+
+    /// Runs BSGS, doubling steps_count and rebuilding the table on each miss
+    /// Fails with `OrderExceeded` once steps_count reaches `max_steps` without a match
+    pub fn run_escalating(
+        &mut self,
+        base: Affine,
+        target: Affine,
+        max_steps: u64,
+    ) -> Result<u64, BsgsError> {
+        loop {
+            if let Some(res) = self.run(base, target) {
+                return Ok(res);
+            }
+            if self.steps_count >= max_steps {
+                return Err(BsgsError::OrderExceeded);
+            }
+            self.steps_count = self.steps_count.saturating_mul(2).min(max_steps);
+        }
+    }
+
+    /// Solves the log of the point `hash_to_grumpkin(seed)` against base
+    pub fn run_from_seed(&mut self, base: Affine, seed: &[u8]) -> Option<u64> {
+        self.run(base, hash_to_grumpkin(seed))
+    }
 
-        let baby_steps = grumpy_bsgs.baby_steps(&super::g());
+    /// Solves target = ±x·base returning `(x, negated)`, negated meaning target = -x·base
+    /// Covers callers handed the negated point, whose log order - x is out of reach
+    /// One table serves the walks for T and then -T. Unverified x-only matches already
+    /// hit -T, so the found scalar is checked to recover the sign
+    pub fn run_canonical(&mut self, base: Affine, target: Affine) -> Option<(u64, bool)> {
+        self.baby_steps(&base);
+        if let Some(res) = self.solve(&base, target) {
+            let found = self.scalar_mul(&base, res);
+            if found == target {
+                return Some((res, false));
+            }
+            if found == -target {
+                return Some((res, true));
+            }
+        }
+        self.solve(&base, -target)
+            .filter(|&res| self.scalar_mul(&base, res) == -target)
+            .map(|res| (res, true))
+    }
+
+    /// BSGS for the first half of `budget`, then `kangaroo` over [0, steps_count⁴) for
+    /// the rest, covering a larger range without more table. None when the budget runs
+    /// out, checked between steps, so an oversized table build can still overrun it
+    #[cfg(feature = "timer")]
+    pub fn solve_within_time(
+        &mut self,
+        base: Affine,
+        target: Affine,
+        budget: Duration,
+    ) -> Option<u64> {
+        let start = std::time::Instant::now();
+
+        self.last_solve_mode = Some(SolveMode::Bsgs);
+        self.baby_steps(&base);
+        let jump = self.giant_step_jump(&base);
+        for (giant_step, current) in GiantStepIterator::new(target, jump, self.steps_count) {
+            if start.elapsed() >= budget / 2 {
+                break;
+            }
+            // Only accept exact matches, the walk goes on past a -target
+            if let Some(baby_step) = self.in_baby_steps(&current) {
+                let res = self.process_result(&baby_step, &giant_step);
+                if self.scalar_mul(&base, res) == target {
+                    return Some(res);
+                }
+            }
+        }
+
+        self.last_solve_mode = Some(SolveMode::Kangaroo);
+        let range = self.steps_count.saturating_pow(4);
+        kangaroo(&base, &target, range, || start.elapsed() < budget)
+    }
 
-        println!("Baby steps: {:?}", baby_steps);
+    /// Algorithm the last `solve_within_time` ended with, None before any
+    pub fn last_solve_mode(&self) -> Option<SolveMode> {
+        self.last_solve_mode
+    }
+
+    /// `run` reporting a `RunProgress` every `progress_interval` giant steps
+    /// Lets a UI show percent complete and estimate the remaining time
+    pub fn run_with_progress(
+        &mut self,
+        base: Affine,
+        target: Affine,
+        mut on_progress: impl FnMut(RunProgress),
+    ) -> Option<u64> {
+        #[cfg(feature = "timer")]
+        let start = std::time::Instant::now();
+        let elapsed = || {
+            #[cfg(feature = "timer")]
+            return Some(start.elapsed());
+            #[cfg(not(feature = "timer"))]
+            None
+        };
+
+        self.baby_steps(&base);
+        let jump = self.giant_step_jump(&base);
+
+        let mut found = None;
+        for (giant_step, current) in GiantStepIterator::new(target, jump, self.steps_count) {
+            if giant_step > 0 && current == target {
+                break;
+            }
+            if self.progress_interval > 0 && giant_step % self.progress_interval == 0 {
+                on_progress(RunProgress {
+                    giant_step,
+                    steps_count: self.steps_count,
+                    elapsed: elapsed(),
+                });
+            }
+            if let Some(baby_step) = self.in_baby_steps(&current) {
+                found = Some((baby_step, giant_step));
+                break;
+            }
+        }
+
+        self.record_giant_steps(found.as_ref().map(|(_, giant)| giant));
+        found.map(|(baby, giant)| self.process_result(&baby, &giant))
+    }
+
+    /// `run` with `step_fn` replacing the giant step `current + giant_step_jump(base)`
+    /// Results are rebuilt as giant·m + baby, so `step_fn` must still translate by
+    /// -m·base (or the `Direction`'s jump), e.g. computed another way or instrumented
+    pub fn run_with_step<F: Fn(&Affine) -> Affine>(
+        &mut self,
+        base: Affine,
+        target: Affine,
+        step_fn: F,
+    ) -> Option<u64> {
+        self.baby_steps(&base);
+
+        let mut current = target;
+        for giant_step in 0..self.steps_count {
+            if let Some(baby_step) = self.in_baby_steps(&current) {
+                return Some(self.process_result(&baby_step, &giant_step));
+            }
+            current = step_fn(&current);
+        }
+        None
+    }
+
+    /// Solves target = (x + offset)·base for x, removing offset·base before the search
+    /// so the table only needs to cover x
+    pub fn run_with_offset(&mut self, base: Affine, target: Affine, offset: u64) -> Option<u64> {
+        let target = (target - self.scalar_mul(&base, offset)).into();
+        self.run(base, target)
+    }
+
+    /// Solves target against the built table and packages the result as a
+    /// `SolveCertificate`, None if it isn't found or the match was only on -target
+    pub fn certificate(&self, base: &Affine, target: &Affine) -> Option<SolveCertificate> {
+        let res = self.solve(base, *target)?;
+        let (giant_index, baby_index) = (res / self.steps_count, res % self.steps_count);
+        let meeting_point = self.scalar_mul(base, baby_index);
+
+        let cert = SolveCertificate {
+            baby_index,
+            giant_index,
+            steps_count: self.steps_count,
+            meeting_point,
+        };
+        verify_certificate(base, target, &cert).then_some(cert)
+    }
+
+    /// `run` returning the scalar as u128, for giant·steps_count + baby past u64::MAX
+    /// Step counters stay u64, only the final combination is widened
+    pub fn run_u128(&mut self, base: Affine, target: Affine) -> Option<u128> {
+        self.baby_steps(&base);
+        let found = self.solve_steps(&base, target);
+        self.record_giant_steps(found.as_ref().map(|(_, giant)| giant));
+
+        let (baby, giant) = found?;
+        let (baby, giant, m) = (baby as u128, giant as u128, self.steps_count as u128);
+        Some(match self.direction {
+            Direction::Forward => giant * m + baby,
+            Direction::Backward => baby * m + giant,
+        })
+    }
+
+    /// (base, target, result) of the last successful `run` or `run_next`
+    pub fn last_solved(&self) -> Option<(Affine, Affine, u64)> {
+        self.last_solved
+    }
+
+    /// Log of target + base right after solving target, previous_result + 1, without a
+    /// walk. Checks previous_result·base is the last solved target, None if it isn't,
+    /// and keeps the new point as the last solved state so calls chain
+    pub fn run_next(&mut self, previous_result: u64) -> Option<u64> {
+        let (base, target, res) = self.last_solved?;
+        if res != previous_result || self.scalar_mul(&base, res) != target {
+            return None;
+        }
+        let next = res.checked_add(1)?;
+        self.last_solved = Some((base, (target + base).into(), next));
+        Some(next)
+    }
+
+    /// Log ratio a/b mod the group order for A = a·base and B = b·base, solving each
+    /// A scalar field element, an integer only when b divides a. None if either log
+    /// isn't found or b is 0
+    pub fn run_ratio(&mut self, base: Affine, a: Affine, b: Affine) -> Option<Fr> {
+        let a = self.run(base, a)?;
+        let b = self.run(base, b)?;
+        Some(Fr::from(a) * Fr::from(b).inverse()?)
+    }
+
+    /// Solves the signed delta x2 - x1 between t1 = x1·base and t2 = x2·base
+    /// as the log of t2 - t1, negative when t1 - t2 is the small multiple
+    pub fn run_difference(&mut self, base: Affine, t1: Affine, t2: Affine) -> Option<i64> {
+        let (delta, negated) = self.run_canonical(base, (t2 - t1).into())?;
+        let delta = i64::try_from(delta).ok()?;
+        Some(if negated { -delta } else { delta })
+    }
+
+    /// Tries each candidate base in turn, rebuilding the table for each
+    /// Returns the index of the first base that solves target and its scalar
+    /// For many targets against the same bases see `MultiBaseGrumpkinBabyGiant`
+    pub fn run_any_base(&mut self, bases: &[Affine], target: Affine) -> Option<(usize, u64)> {
+        bases
+            .iter()
+            .enumerate()
+            .find_map(|(idx, base)| self.run(*base, target).map(|res| (idx, res)))
+    }
+
+    /// Giant walk against a caller-owned table of (x-coordinate, k) sorted by x, e.g.
+    /// one borrowed per call on embedded targets, so nothing is allocated at solve time
+    /// `jump` is -(steps_count·base) as from `compute_jump`. Like `KeyMode::X`, a match
+    /// on -target's x isn't told apart
+    pub fn run_borrowed(
+        target: Affine,
+        table: &[(Fq, u64)],
+        jump: &Affine,
+        steps_count: u64,
+    ) -> Option<u64> {
+        GiantStepIterator::new(target, *jump, steps_count).find_map(|(giant_step, current)| {
+            let idx = table.binary_search_by_key(&current.x, |(x, _)| *x).ok()?;
+            Some(giant_step * steps_count + table[idx].1)
+        })
+    }
+
+    /// Solves target = k·(scale·base) for k, building the table against scale·base
+    /// None for a zero scale, whose effective base is the identity
+    pub fn run_scaled_base(&mut self, base: Affine, target: Affine, scale: u64) -> Option<u64> {
+        if scale == 0 {
+            return None;
+        }
+        self.run(self.scalar_mul(&base, scale), target)
+    }
+
+    /// Runs BSGS on points given in the isomorphic model, see `IsoAffine`
+    pub fn run_isomorphic(&mut self, base: IsoAffine, target: IsoAffine) -> Option<u64> {
+        self.run(from_isomorphic(base), from_isomorphic(target))
+    }
+
+    /// Runs BSGS returning a typed `GrumpkinScalar`
+    pub fn run_typed(&mut self, base: Affine, target: Affine) -> Option<GrumpkinScalar> {
+        self.run(base, target).map(GrumpkinScalar)
+    }
+
+    /// Runs BSGS and returns the scalar as `Fr` to feed back into curve math
+    pub fn run_fr(&mut self, base: Affine, target: Affine) -> Option<Fr> {
+        self.run(base, target).map(scalar_to_fr)
+    }
+
+    /// Runs at most `budget` giant steps starting at giant index `start_giant`
+    /// Builds the baby steps unless the table, frozen or not, was built for base with the
+    /// current steps_count, returns `Err(Checkpoint)` when the budget runs out so the
+    /// search can be continued with `resume`
+    /// Keys don't tell P from -P, so `start_giant` must not be past the scalar's giant step
+    pub fn run_from_checkpoint(
+        &mut self,
+        base: Affine,
+        target: Affine,
+        start_giant: u64,
+        budget: u64,
+    ) -> Result<Option<u64>, Checkpoint> {
+        if !self.table_built_for(&base) {
+            self.baby_steps(&base);
+        }
+
+        let jump = self.giant_step_jump(&base);
+        let checkpoint = Checkpoint {
+            current: (target + self.scalar_mul(&jump, start_giant)).into(),
+            giant_step: start_giant,
+        };
+        self.resume(&base, checkpoint, budget)
+    }
+
+    /// Continues an interrupted walk for at most `budget` giant steps
+    /// `Ok(None)` means all giant steps were scanned without a match
+    pub fn resume(
+        &self,
+        base: &Affine,
+        checkpoint: Checkpoint,
+        budget: u64,
+    ) -> Result<Option<u64>, Checkpoint> {
+        let jump = self.giant_step_jump(base);
+        let Checkpoint {
+            mut current,
+            mut giant_step,
+        } = checkpoint;
+        let end = giant_step.saturating_add(budget).min(self.steps_count);
+
+        while giant_step < end {
+            if let Some(baby_step) = self.in_baby_steps(&current) {
+                return Ok(Some(self.process_result(&baby_step, &giant_step)));
+            }
+            current = self.el_operation(&current, &jump);
+            giant_step += 1;
+        }
+
+        if giant_step < self.steps_count {
+            Err(Checkpoint {
+                current,
+                giant_step,
+            })
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Lazily solves each target against the already built table as the iterator is consumed
+    /// Yields None for every target if no table has been built yet
+    pub fn solve_stream<I: Iterator<Item = Affine>>(
+        &self,
+        targets: I,
+    ) -> impl Iterator<Item = Option<u64>> {
+        targets.map(|target| self.table_base.and_then(|base| self.solve(&base, target)))
+    }
+
+    /// Solves every point of a file of decimal `x,y` lines against one table for base
+    /// Malformed lines and points off the curve give None without stopping the batch
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn run_file(
+        &mut self,
+        base: Affine,
+        path: &std::path::Path,
+    ) -> io::Result<Vec<Option<u64>>> {
+        let contents = std::fs::read_to_string(path)?;
+        self.baby_steps(&base);
+
+        let results = contents
+            .lines()
+            .map(|line| {
+                let (x, y) = line.trim().split_once(',')?;
+                let target = try_grumpkin_str_to_point(x.trim(), y.trim()).ok()?;
+                self.solve(&base, target)
+            })
+            .collect();
+        Ok(results)
+    }
+
+    /// Debugging aid, not cryptographically meaningful: on a miss, reports the nearest
+    /// baby/giant combination `giant·m + baby` whose point lies within `tolerance`
+    /// additions or subtractions of base from the walk, as `(scalar, offset)` with
+    /// target = (scalar + offset)·base. Exact matches come back with offset 0
+    pub fn run_nearest(
+        &mut self,
+        base: Affine,
+        target: Affine,
+        tolerance: u64,
+    ) -> Option<(u64, i64)> {
+        if let Some(res) = self.run(base, target) {
+            return Some((res, 0));
+        }
+
+        let jump = self.giant_step_jump(&base);
+        let mut current = target;
+        for giant_step in 0..self.steps_count {
+            // Offset 0 is current itself, which run already missed
+            let (mut below, mut above) = (current, current);
+            for offset in 1..=tolerance {
+                below = (below - base).into();
+                above = (above + base).into();
+                let offset = offset as i64;
+                if let Some(baby_step) = self.in_baby_steps(&below) {
+                    return Some((self.process_result(&baby_step, &giant_step), offset));
+                }
+                if let Some(baby_step) = self.in_baby_steps(&above) {
+                    return Some((self.process_result(&baby_step, &giant_step), -offset));
+                }
+            }
+            current = self.el_operation(&current, &jump);
+        }
+        None
+    }
+
+    /// Writes the table as `index,x_coordinate` CSV rows sorted by index
+    /// x-coordinates are decimal, after an `index,x_coordinate` header row
+    pub fn write_csv<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let mut rows: Vec<_> = self.baby_steps.iter().map(|(key, i)| (*i, key)).collect();
+        rows.sort_unstable_by_key(|(i, _)| *i);
+
+        writeln!(w, "index,x_coordinate")?;
+        for (i, key) in rows {
+            writeln!(w, "{},{}", i, key_to_fq(key))?;
+        }
+        Ok(())
+    }
+
+    /// Checks target = scalar·base for every pair with a single multi-scalar multiplication
+    /// Uses a random linear combination, sum(r_i·T_i) - (sum(r_i·x_i))·base == 0, with
+    /// 64-bit coefficients from a randomly keyed hasher, so a bad pair slips by with
+    /// probability around 2^-64
+    pub fn verify_batch(&self, base: &Affine, pairs: &[(Affine, u64)]) -> bool {
+        let random = RandomState::new();
+
+        let mut points = Vec::with_capacity(pairs.len() + 1);
+        let mut coeffs = Vec::with_capacity(pairs.len() + 1);
+        let mut base_coeff = Fr::from(0_u64);
+        for (i, (target, scalar)) in pairs.iter().enumerate() {
+            let r = Fr::from(random.hash_one(i));
+            points.push(*target);
+            coeffs.push(r);
+            base_coeff += r * scalar_to_fr(*scalar);
+        }
+        points.push(*base);
+        coeffs.push(-base_coeff);
+
+        Projective::msm(&points, &coeffs)
+            .map(|sum| sum.is_zero())
+            .unwrap_or(false)
+    }
+
+    /// Recomputes `sample` random table indices k as k·base and checks each is stored
+    /// under its key, catching corrupted tables before they silently mis-solve
+    /// Checks every index when `sample` covers the whole table
+    pub fn verify_table(&self, base: &Affine, sample: usize) -> bool {
+        let step = match self.direction {
+            Direction::Forward => *base,
+            Direction::Backward => self.scalar_mul(base, self.steps_count),
+        };
+        let entries = self.steps_count.div_ceil(self.stride);
+        let stored = |k: u64| {
+            let key = self.key(&self.scalar_mul(&step, k));
+            self.table_get(&key) == Some(k)
+                || self
+                    .collided
+                    .get(&key)
+                    .is_some_and(|extra| extra.contains(&k))
+        };
+
+        if sample as u64 >= entries {
+            return (0..entries).all(|i| stored(i * self.stride));
+        }
+        let random = RandomState::new();
+        (0..sample).all(|i| stored(random.hash_one(i) % entries * self.stride))
+    }
+
+    /// Approximate heap memory held by the baby step table in bytes
+    /// Counts allocated capacity plus one control byte per bucket
+    pub fn approx_table_bytes(&self) -> usize {
+        let frozen = self.frozen.as_ref().map_or(0, FrozenTable::approx_bytes);
+        self.baby_steps.capacity() * (size_of::<(FqKey, u64)>() + 1) + frozen
+    }
+
+    /// Moves the built table behind a minimal perfect hash, see `FrozenTable`
+    /// Lookups stay O(1) without collisions in less memory than the HashMap, which is
+    /// left empty, so `get_baby_steps` and the index views only see later additions
+    pub fn freeze(&mut self) {
+        let empty = HashMap::with_hasher(self.hasher());
+        let baby_steps = std::mem::replace(&mut self.baby_steps, empty);
+        self.frozen = Some(FrozenTable::new(&baby_steps));
+    }
+
+    /// Baby step stored under key, in the frozen table or the HashMap
+    fn table_get(&self, key: &FqKey) -> Option<u64> {
+        self.frozen
+            .as_ref()
+            .and_then(|frozen| frozen.get(key))
+            .or_else(|| self.baby_steps.get(key).copied())
+    }
+
+    /// Whether the current table holds base's baby steps for the current steps_count
+    fn table_built_for(&self, base: &Affine) -> bool {
+        self.table_base == Some(*base)
+            && self.table_steps_count == Some(self.steps_count)
+            && self.table_len() > 0
+    }
+
+    fn table_len(&self) -> usize {
+        self.frozen.as_ref().map_or(0, FrozenTable::len) + self.baby_steps.len()
+    }
+}
+
+/// Estimated `approx_table_bytes` for `entries` baby steps, before the table is built
+/// The map grows its power of two bucket count to stay under 7/8 load
+/// Saturates at usize::MAX for counts no table could hold
+pub fn estimate_table_bytes(entries: u64) -> usize {
+    let buckets = usize::try_from(entries)
+        .unwrap_or(usize::MAX)
+        .saturating_mul(8)
+        .div_ceil(7)
+        .checked_next_power_of_two()
+        .unwrap_or(usize::MAX);
+    (buckets / 8 * 7).saturating_mul(size_of::<(FqKey, u64)>() + 1)
+}
+
+/// Picks the (steps_count, stride) covering scalars up to `max_scalar` with the lowest
+/// modelled cost within `max_bytes` of table memory
+/// Cost is the m/stride table additions plus half of the giant steps the scalar range
+/// needs, each paying up to `stride` lookups. If nothing fits, the smallest table is returned
+pub fn autotune(max_scalar: u64, max_bytes: usize) -> BsgsConfig {
+    // run only walks steps_count giant steps, so steps_count² must exceed max_scalar
+    let min_steps = max_scalar.isqrt() + 1;
+
+    let mut best: Option<(u64, usize, BsgsConfig)> = None;
+    for steps_count in (0..8).filter_map(|i| min_steps.checked_shl(i)) {
+        for stride in (0..17)
+            .map(|i| 1_u64 << i)
+            .take_while(|&s| s <= steps_count)
+        {
+            let entries = steps_count.div_ceil(stride);
+            let bytes = estimate_table_bytes(entries);
+            let giant_steps = max_scalar / steps_count + 1;
+            let cost = entries + giant_steps.div_ceil(2) * stride;
+
+            let config = BsgsConfig {
+                steps_count,
+                stride,
+                ..BsgsConfig::default()
+            };
+            let better = match best {
+                None => true,
+                Some((best_cost, best_bytes, _)) => {
+                    let (fits, best_fits) = (bytes <= max_bytes, best_bytes <= max_bytes);
+                    match (fits, best_fits) {
+                        (true, false) => true,
+                        (false, true) => false,
+                        (true, true) => cost < best_cost,
+                        (false, false) => bytes < best_bytes,
+                    }
+                }
+            };
+            if better {
+                best = Some((cost, bytes, config));
+            }
+        }
+    }
+    best.map(|(_, _, config)| config).unwrap_or_default()
+}
+
+/// steps_count minimizing the modelled `m·baby_cost + (max_scalar / m)·giant_cost`
+/// Away from sqrt(max_scalar) when a baby step costs more or less than a giant step.
+/// Below sqrt(max_scalar) the walk needs more than steps_count giant steps, see `run_in_range`
+pub fn balanced_steps_count(max_scalar: u64, baby_cost_ns: u64, giant_cost_ns: u64) -> u64 {
+    let max_scalar = max_scalar.max(1);
+    if baby_cost_ns == 0 {
+        return max_scalar;
+    }
+
+    // The derivative vanishes at sqrt(max_scalar·giant_cost / baby_cost)
+    let optimum = (max_scalar as f64 * giant_cost_ns as f64 / baby_cost_ns as f64).sqrt();
+    let cost = |m: u64| {
+        m as f64 * baby_cost_ns as f64 + max_scalar.div_ceil(m) as f64 * giant_cost_ns as f64
+    };
+    let low = (optimum.floor() as u64).clamp(1, max_scalar);
+    let high = (optimum.ceil() as u64).clamp(1, max_scalar);
+    if cost(high) < cost(low) { high } else { low }
+}
+
+/// Grumpkin points as a `Group`, the backend's arithmetic goes through this
+impl Group for Affine {
+    fn identity() -> Self {
+        AffineRepr::zero()
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        (*self + *rhs).into()
+    }
+
+    fn neg(&self) -> Self {
+        -*self
+    }
+
+    fn mul_small(&self, k: u64) -> Self {
+        (*self * Fr::from(k)).into()
+    }
+}
+
+/// Implementation for u128 modular exponentiation
+impl BabyGiantOps for GrumpkinBabyGiant {
+    type El = Affine;
+    type Scalar = u64;
+    type Key = FqKey;
+
+    fn steps_count(&self) -> Self::Scalar {
+        self.steps_count
+    }
+
+    /// Table key of an element under the configured `KeyMode`
+    fn key(&self, el: &Affine) -> FqKey {
+        mode_key(self.key_mode, &self.canonical(el))
+    }
+
+    /// The canonically serialized x-coordinate, whatever the `KeyMode`
+    fn element_bytes(&self, el: &Affine) -> Vec<u8> {
+        fq_key(&self.canonical(el)).to_vec()
+    }
+
+    fn store_baby_step(&mut self, key: FqKey, baby_step: u64) {
+        self.insert_baby_step(key, baby_step);
+    }
+
+    fn clear_baby_steps(&mut self) {
+        self.baby_steps.clear();
+        self.frozen = None;
+        self.x_collisions = 0;
+        self.collided.clear();
+    }
+
+    /// Overrides the default walk to batch normalizations and honour the stride and
+    /// direction, storing through the same `key` and collision handling
+    fn baby_steps(&mut self, base: &Self::El) {
+        debug!("bsgs: building {} baby steps", self.steps_count);
+        self.clear_baby_steps();
+
+        self.baby_steps_range(base, 0, self.steps_count);
+
+        // Drop slack kept from earlier, larger builds before the giant phase
+        self.baby_steps.shrink_to_fit();
+        debug!(
+            "bsgs: built {} baby steps, {} x collisions",
+            self.baby_steps.len(),
+            self.x_collisions
+        );
+    }
+
+    fn identity(&self) -> Self::El {
+        <Affine as Group>::identity()
+    }
+
+    /// Any point flagged at infinity is the identity whatever its stored coordinates
+    fn canonical(&self, el: &Self::El) -> Self::El {
+        if el.infinity { Affine::identity() } else { *el }
+    }
+
+    fn el_operation(&self, lhs: &Self::El, rhs: &Self::El) -> Self::El {
+        Group::add(lhs, rhs)
+    }
+
+    fn scalar_mul(&self, el: &Self::El, k: u64) -> Self::El {
+        el.mul_small(k)
+    }
+
+    /// Grumpkin has prime order, so this is a no-op kept for curves with cofactors
+    fn clear_cofactor(&self, el: &Self::El) -> Self::El {
+        el.clear_cofactor()
+    }
+
+    /// Always recomputed from the current steps_count, panics if that no longer
+    /// matches the one the table was built with since results would be garbage
+    fn giant_step_jump(&self, base: &Self::El) -> Self::El {
+        if let Some(table_steps_count) = self.table_steps_count {
+            assert_eq!(
+                table_steps_count, self.steps_count,
+                "steps_count changed since the baby steps were built"
+            );
+        }
+        match self.direction {
+            Direction::Forward => self.scalar_mul(base, self.steps_count).neg(),
+            Direction::Backward => base.neg(),
+        }
+    }
+
+    /// Same walk as the default over a `GiantStepIterator`, so each giant step is a
+    /// mixed addition into a projective accumulator
+    /// A step schedule jumps straight to each scheduled index instead
+    fn solve_steps(&self, base: &Affine, target: Affine) -> Option<(u64, u64)> {
+        self.giant_lookups.set(0);
+        if self.bidirectional {
+            return self.solve_steps_bidirectional(base, target);
+        }
+        let jump = self.giant_step_jump(base);
+        // u128 as run_u128 goes through here with scalars past u64
+        let accepted = |&baby_step: &u64, &giant_step: &u64| {
+            let (baby, giant, m) = (
+                baby_step as u128,
+                giant_step as u128,
+                self.steps_count as u128,
+            );
+            let res = match self.direction {
+                Direction::Forward => giant * m + baby,
+                Direction::Backward => baby * m + giant,
+            };
+            !self.verify_results || *base * Fr::from(res) == target.into_group()
+        };
+
+        if let Some(step_schedule) = &self.step_schedule {
+            return step_schedule.iter().find_map(|&giant_step| {
+                let current = self.el_operation(&target, &self.scalar_mul(&jump, giant_step));
+                self.giant_lookups.set(self.giant_lookups.get() + 1);
+                self.in_baby_steps(&current)
+                    .filter(|baby_step| accepted(baby_step, &giant_step))
+                    .map(|baby_step| (baby_step, giant_step))
+            });
+        }
+
+        for (giant_step, current) in GiantStepIterator::new(target, jump, self.steps_count) {
+            // Walk cycled through the whole group, or an identity jump can't move
+            if giant_step > 0 && current == target {
+                break;
+            }
+            self.giant_lookups.set(self.giant_lookups.get() + 1);
+            if let Some(baby_step) = self.in_baby_steps(&current)
+                && accepted(&baby_step, &giant_step)
+            {
+                return Some((baby_step, giant_step));
+            }
+        }
+        None
+    }
+
+    /// giant·m + baby only recovers the scalar for baby < m, which `in_baby_steps`
+    /// guarantees by rejecting larger stored indices
+    fn process_result(&self, baby: &u64, giant: &u64) -> u64 {
+        let step_count = self.steps_count;
+        debug_assert!(*baby < step_count, "baby step {baby} outside the table");
+        match self.direction {
+            Direction::Forward => giant * step_count + baby,
+            // Table holds the multiple of m, the walk counts single steps
+            Direction::Backward => baby * step_count + giant,
+        }
+    }
+
+    fn in_baby_steps(&self, target: &Self::El) -> Option<Self::Scalar> {
+        // With a stride, step down to the nearest stored multiple
+        let mut current = *target;
+        let mut offset = 0;
+        loop {
+            let key = self.key(&current);
+            if let Some(baby_step) = self.table_get(&key) {
+                // Pick whichever stored index actually maps to the point
+                let baby_step = self
+                    .collided
+                    .get(&key)
+                    .and_then(|extra| {
+                        std::iter::once(&baby_step)
+                            .chain(extra)
+                            .find(|&&k| self.scalar_mul(&self.table_step, k) == current)
+                            .copied()
+                    })
+                    .unwrap_or(baby_step);
+                // An index past the table (corrupted, or a stride overshooting m) would
+                // break giant·m + baby, the next giant step finds it in range instead
+                let baby_step = baby_step + offset;
+                return (baby_step < self.steps_count).then_some(baby_step);
+            }
+            offset += 1;
+            if offset == self.stride {
+                return None;
+            }
+            current = (current - self.table_step).into();
+        }
+    }
+
+    fn record_giant_steps(&mut self, giant_steps: Option<&u64>) {
+        self.last_giant_steps = giant_steps.copied();
+    }
+
+    fn cached_result(&mut self, base: &Self::El, target: &Self::El) -> Option<u64> {
+        let res = self.result_cache.as_ref()?.get(&(*base, *target)).copied();
+        if let Some(res) = res {
+            self.last_solved = Some((*base, *target, res));
+        }
+        if res.is_some() {
+            self.cache_hits += 1;
+        }
+        res
+    }
+
+    fn cache_result(&mut self, base: &Self::El, target: &Self::El, res: &u64) {
+        self.last_solved = Some((*base, *target, *res));
+        if let Some(cache) = self.result_cache.as_mut() {
+            cache.insert((*base, *target), *res);
+        }
+    }
+}
+
+/// One baby step table shared by several bases, each entry tagged with its base index
+/// Lets logs against a few fixed generators be solved without a table build per base
+#[derive(Clone, PartialEq, Eq)]
+pub struct MultiBaseGrumpkinBabyGiant {
+    steps_count: u64,
+    bases: Vec<Affine>,
+    baby_steps: HashMap<FqKey, (u8, u64)>,
+    /// Further entries under keys already taken, from bases sharing x-coordinates
+    collided: HashMap<FqKey, Vec<(u8, u64)>>,
+}
+
+impl MultiBaseGrumpkinBabyGiant {
+    /// Builds the combined table of k·base for k in 0..steps_count and every base
+    pub fn new(steps_count: u64, bases: &[Affine]) -> Self {
+        assert!(bases.len() <= u8::MAX as usize + 1, "at most 256 bases");
+
+        let mut baby_steps = HashMap::new();
+        let mut collided: HashMap<FqKey, Vec<(u8, u64)>> = HashMap::new();
+        for (base_idx, base) in bases.iter().enumerate() {
+            let mut current = Affine::identity();
+            for baby_step in 0..steps_count {
+                // The identity is shared, only the first base stores it
+                if baby_step > 0 || base_idx == 0 {
+                    let key = fq_key(&current);
+                    let entry = (base_idx as u8, baby_step);
+                    match baby_steps.entry(key) {
+                        Entry::Vacant(vacant) => {
+                            vacant.insert(entry);
+                        }
+                        Entry::Occupied(_) => collided.entry(key).or_default().push(entry),
+                    }
+                }
+                current = (current + base).into();
+            }
+        }
+
+        Self {
+            steps_count,
+            bases: bases.to_vec(),
+            baby_steps,
+            collided,
+        }
+    }
+
+    /// Finds `(base_idx, x)` with target = x·bases[base_idx]
+    /// Walks every base a giant step at a time, so the smallest giant step wins
+    /// Keys are x-only, so each candidate is checked against target, trying the
+    /// matched point and then its negation
+    pub fn run_multi(&self, target: Affine) -> Option<(u8, u64)> {
+        let jumps: Vec<Affine> = self
+            .bases
+            .iter()
+            .map(|base| (-(*base * Fr::from(self.steps_count))).into())
+            .collect();
+        let mut currents = vec![target; self.bases.len()];
+
+        for giant_step in 0..self.steps_count {
+            for (base_idx, current) in currents.iter_mut().enumerate() {
+                let key = fq_key(current);
+                let candidates = self
+                    .baby_steps
+                    .get(&key)
+                    .into_iter()
+                    .chain(self.collided.get(&key).into_iter().flatten());
+                for &(idx, baby_step) in candidates {
+                    // 0·base is the identity for every base
+                    if idx as usize != base_idx && baby_step != 0 {
+                        continue;
+                    }
+                    // current = ±baby_step·base
+                    let giant = giant_step * self.steps_count;
+                    let verified = [Some(giant + baby_step), giant.checked_sub(baby_step)]
+                        .into_iter()
+                        .flatten()
+                        .find(|&res| self.bases[base_idx] * Fr::from(res) == target);
+                    if let Some(res) = verified {
+                        return Some((base_idx as u8, res));
+                    }
+                }
+                *current = (*current + jumps[base_idx]).into();
+            }
+        }
+        None
+    }
+}
+
+/// Solves independent `(base, target, steps_count)` instances, each with its own table
+/// Runs them in parallel with the `rayon` feature, one after another otherwise
+pub fn run_instances(instances: &[(Affine, Affine, u64)]) -> Vec<Option<u64>> {
+    let solve = |&(base, target, steps_count): &(Affine, Affine, u64)| {
+        GrumpkinBabyGiant::try_new(steps_count)
+            .ok()
+            .and_then(|mut grumpy_bsgs| grumpy_bsgs.run(base, target))
+    };
+
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        instances.par_iter().map(solve).collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        instances.iter().map(solve).collect()
+    }
+}
+
+/// Solves target against `g()` with a `size` table, 0 when not found or size is 0
+pub fn grumpkin_bsgs(target: Affine, size: u64) -> u64 {
+    let Ok(mut grumpy_bsgs) = GrumpkinBabyGiant::try_new(size) else {
+        return 0;
+    };
+
+    let res = grumpy_bsgs.run(g(), target);
+
+    res.unwrap_or_default()
+}
+
+pub fn grumpkin_bsgs_32(target: Affine) -> u64 {
+    grumpkin_bsgs(target, 65_536)
+}
+
+pub fn grumpkin_bsgs_40(target: Affine) -> u64 {
+    grumpkin_bsgs(target, 1_048_576)
+}
+
+/// Pollard's kangaroo for target = x·base with x < range, about 4·√range additions in
+/// constant memory. A wild kangaroo can miss the trap, so it retries with freshly
+/// salted jumps until `keep_going` returns false
+pub fn kangaroo(
+    base: &Affine,
+    target: &Affine,
+    range: u64,
+    mut keep_going: impl FnMut() -> bool,
+) -> Option<u64> {
+    if range == 0 {
+        return None;
+    }
+
+    // Power of two jumps averaging about √range / 2
+    let mean = (range.isqrt() / 2).max(1);
+    let jump_count = (1..64_u64)
+        .find(|&k| ((1 << k) - 1) / k >= mean)
+        .unwrap_or(63);
+    let jumps: Vec<(u128, Affine)> = (0..jump_count)
+        .map(|i| (1 << i, (*base * Fr::from(1_u64 << i)).into_affine()))
+        .collect();
+    let start = (*base * Fr::from(range)).into_affine();
+
+    for salt in 0_u64.. {
+        // Jump picked by the point's x, salted so each attempt walks a fresh path
+        let hop = |p: &Affine| {
+            let word = u64::from_le_bytes(fq_key(p)[..8].try_into().unwrap());
+            jumps[((word ^ salt.wrapping_mul(0x9e37_79b9_7f4a_7c15)) % jump_count) as usize]
+        };
+
+        // Tame kangaroo from range·base sets the trap about range / 2 further
+        let mut trap = start;
+        let mut trap_distance = range as u128;
+        for _ in 0..2 * mean {
+            if !keep_going() {
+                return None;
+            }
+            let (distance, jump) = hop(&trap);
+            trap = (trap + jump).into_affine();
+            trap_distance += distance;
+        }
+
+        // Wild kangaroo sits at (x + wild_distance)·base
+        let mut wild = *target;
+        let mut wild_distance = 0_u128;
+        while wild_distance <= trap_distance {
+            if !keep_going() {
+                return None;
+            }
+            if wild == trap {
+                return u64::try_from(trap_distance - wild_distance).ok();
+            }
+            let (distance, jump) = hop(&wild);
+            wild = (wild + jump).into_affine();
+            wild_distance += distance;
+        }
+    }
+    None
+}
+
+pub fn grumpkin_str_to_point(x: &str, y: &str) -> Affine {
+    Affine::new_unchecked(
+        Fq::new(BigInt::from_str(x).unwrap()),
+        Fq::new(BigInt::from_str(y).unwrap()),
+    )
+}
+
+/// Parses decimal coordinates into a Grumpkin point without panicking
+/// Rejects non-numeric and out-of-field coordinates and points off the curve
+pub fn try_grumpkin_str_to_point(x: &str, y: &str) -> Result<Affine, ParsePointError> {
+    let parse = |coord: &str| {
+        if coord.is_empty() || !coord.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParsePointError::InvalidNumber);
+        }
+        BigInt::from_str(coord)
+            .ok()
+            .and_then(Fq::from_bigint)
+            .ok_or(ParsePointError::OutOfField)
+    };
+
+    let point = Affine::new_unchecked(parse(x)?, parse(y)?);
+    if !point.is_on_curve() {
+        return Err(ParsePointError::OffCurve);
+    }
+    Ok(point)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use ark_ec::CurveGroup;
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn grumpkin_bsgs_40() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(1_048_576);
+
+        let x_num = 840368900803_u64;
+        let target = grumpkin_scalar_base(x_num);
+
+        let now = Instant::now();
+
+        let res = grumpy_bsgs.run(g(), target);
+
+        println!("Result: {:?}", res);
+        println!("\n\nGrumpkin BSGS took: {:.2?}", now.elapsed());
+
+        assert!(res.unwrap() == x_num, "Incorrect result");
+    }
+
+    #[test]
+    fn grumpkin_bsgs_40_range() {
+        let m = 1_048_576_u64;
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(m);
+        grumpy_bsgs.baby_steps(&g());
+
+        for x_num in [
+            m,
+            m + 1,
+            1 << 32,
+            (1 << 32) + 1,
+            (1 << 36) + 12345,
+            (1 << 40) - m,
+            (1 << 40) - 1,
+        ] {
+            let target = grumpkin_scalar_base(x_num);
+            assert_eq!(grumpy_bsgs.solve(&g(), target), Some(x_num), "{x_num}");
+        }
+
+        // First scalar past steps_count²
+        let target = grumpkin_scalar_base(1_u64 << 40);
+        assert_eq!(grumpy_bsgs.solve(&g(), target), None);
+    }
+
+    #[test]
+    fn grumpkin_projective_walk_matches_default() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        let mut generic = crate::GroupBabyGiant::<Affine>::new(64);
+        for x_num in [0_u64, 1, 63, 64, 65, 4095, 4096, 1 << 20] {
+            let target = grumpkin_scalar_base(x_num);
+            assert_eq!(grumpy_bsgs.run(g(), target), generic.run(g(), target));
+        }
+    }
+
+    /// Times the projective walk against the default affine one, run with --ignored
+    #[test]
+    #[ignore]
+    fn grumpkin_projective_walk_bench() {
+        let target = grumpkin_scalar_base((1 << 32) - 1);
+
+        let now = Instant::now();
+        assert!(GrumpkinBabyGiant::new(65536).run(g(), target).is_some());
+        println!("Projective walk took: {:.2?}", now.elapsed());
+
+        let now = Instant::now();
+        assert!(
+            crate::GroupBabyGiant::<Affine>::new(65536)
+                .run(g(), target)
+                .is_some()
+        );
+        println!("Affine walk took: {:.2?}", now.elapsed());
+    }
+
+    #[test]
+    fn grumpkin_bsgs_32() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(65536);
+
+        let x_num = 4294967295_u64;
+        let target = grumpkin_scalar_base(x_num);
+
+        let now = Instant::now();
+
+        let res = grumpy_bsgs.run(g(), target);
+
+        println!("Result: {:?}", res);
+        println!("\n\nGrumpkin BSGS took: {:.2?}", now.elapsed());
+
+        assert!(res.unwrap() == x_num, "Incorrect result");
+    }
+
+    // #[test]
+    // pub fn grumpkin_bsgs_str() {
+    //     let pt_coords = (
+    //         "18404411293574529506939754020345889193409751730106812839425939610401353525304",
+    //         "18142326956230734014841872757503391093517090171806600353970481267506310918723",
+    //     );
+
+    //     let r = super::grumpkin_bsgs_str(pt_coords.0, pt_coords.1);
+
+    //     assert!(r == 35235);
+    // }
+
+    #[test]
+    fn grumpkin_run_from_seed() {
+        let p = hash_to_grumpkin(b"baby giant");
+        assert!(p.is_on_curve());
+        assert_eq!(p, hash_to_grumpkin(b"baby giant"));
+        assert_ne!(p, hash_to_grumpkin(b"giant baby"));
+
+        // A hashed point's log is practically never small
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(16);
+        assert_eq!(grumpy_bsgs.run_from_seed(g(), b"baby giant"), None);
+        // With the hashed point as base its own log is 1
+        assert_eq!(grumpy_bsgs.run_from_seed(p, b"baby giant"), Some(1));
+    }
+
+    #[test]
+    fn grumpkin_try_str_to_point() {
+        let p: Affine = grumpkin_scalar_base(35235_u64);
+        let (x, y) = (p.x.to_string(), p.y.to_string());
+        assert_eq!(try_grumpkin_str_to_point(&x, &y), Ok(p));
+
+        assert_eq!(
+            try_grumpkin_str_to_point("12ab", &y),
+            Err(ParsePointError::InvalidNumber)
+        );
+        assert_eq!(
+            try_grumpkin_str_to_point(&x, ""),
+            Err(ParsePointError::InvalidNumber)
+        );
+        // Base field modulus itself and a number overflowing 256 bits
+        assert_eq!(
+            try_grumpkin_str_to_point(
+                "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+                &y
+            ),
+            Err(ParsePointError::OutOfField)
+        );
+        assert_eq!(
+            try_grumpkin_str_to_point(&"9".repeat(80), &y),
+            Err(ParsePointError::OutOfField)
+        );
+        assert_eq!(
+            try_grumpkin_str_to_point("1", "2"),
+            Err(ParsePointError::OffCurve)
+        );
+    }
+
+    #[test]
+    pub fn grumpkin_baby_steps() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(32);
+
+        grumpy_bsgs.baby_steps(&super::g());
+
+        let baby_steps = grumpy_bsgs.get_baby_steps();
+        assert_eq!(baby_steps.len(), 32);
+        for k in 0..32_u64 {
+            let point: Affine = grumpkin_scalar_base(k);
+            assert_eq!(
+                baby_steps.get(&fq_key(&point)),
+                Some(&k),
+                "Entry {k} should map {k}·g"
+            );
+        }
+    }
+
+    #[test]
+    fn grumpkin_last_giant_steps() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        assert_eq!(grumpy_bsgs.last_giant_steps(), None);
+
+        // 1000 = 15·64 + 40
+        let target = grumpkin_scalar_base(1000_u64);
+        assert_eq!(grumpy_bsgs.run(g(), target), Some(1000));
+        assert_eq!(grumpy_bsgs.last_giant_steps(), Some(15));
+
+        // Out of range for 64², nothing matched
+        let target = grumpkin_scalar_base(4096_u64);
+        assert_eq!(grumpy_bsgs.run(g(), target), None);
+        assert_eq!(grumpy_bsgs.last_giant_steps(), None);
+    }
+
+    #[test]
+    fn grumpkin_solve_reuses_table() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(256);
+        grumpy_bsgs.baby_steps(&g());
+
+        for x_num in [3_u64, 1000, 65535] {
+            let target = grumpkin_scalar_base(x_num);
+            assert_eq!(grumpy_bsgs.solve(&g(), target), Some(x_num));
+        }
+        assert_eq!(grumpy_bsgs.get_baby_steps().len(), 256);
+    }
+
+    #[test]
+    #[should_panic(expected = "steps_count changed since the baby steps were built")]
+    fn grumpkin_stale_table_guard() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(256);
+        grumpy_bsgs.baby_steps(&g());
+        assert_eq!(
+            grumpy_bsgs.solve(&g(), grumpkin_scalar_base(1000_u64)),
+            Some(1000)
+        );
+
+        grumpy_bsgs.steps_count = 128;
+        grumpy_bsgs.solve(&g(), grumpkin_scalar_base(1000_u64));
+    }
+
+    #[test]
+    fn grumpkin_scalar_base_roundtrip() {
+        for x in [0_u64, 1, 255, 65_535] {
+            assert_eq!(g() * Fr::from(x), grumpkin_scalar_base(x));
+            assert_eq!(grumpkin_bsgs(grumpkin_scalar_base(x), 256), x);
+        }
+    }
+
+    #[test]
+    fn grumpkin_isomorphic() {
+        let base = to_isomorphic(&g()).unwrap();
+        let target = to_isomorphic(&grumpkin_scalar_base(4321)).unwrap();
+        assert_eq!(base.y.square(), base.x.square() * base.x + Fq::from(ISO_B));
+        assert_eq!(from_isomorphic(base), g());
+        assert_eq!(to_isomorphic(&Affine::identity()), None);
+
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(128);
+        assert_eq!(grumpy_bsgs.run_isomorphic(base, target), Some(4321));
+    }
+
+    #[test]
+    fn grumpkin_generic_group() {
+        let mut generic = crate::GroupBabyGiant::<Affine>::new(64);
+        for x_num in [0_u64, 63, 64, 4000] {
+            assert_eq!(generic.run(g(), grumpkin_scalar_base(x_num)), Some(x_num));
+        }
+    }
+
+    #[test]
+    fn grumpkin_baby_steps_by_index() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(32);
+        grumpy_bsgs.baby_steps(&g());
+
+        // Contiguous from the identity at 0 up to steps_count - 1
+        let by_index = grumpy_bsgs.baby_steps_by_index();
+        assert!(by_index.keys().copied().eq(0..32));
+        for (&k, x) in &by_index {
+            assert_eq!(*x, grumpkin_scalar_base(k).x);
+        }
+    }
+
+    #[test]
+    fn grumpkin_zero_steps_count() {
+        assert_eq!(
+            GrumpkinBabyGiant::try_new(0).err(),
+            Some(BsgsError::InvalidParams)
+        );
+        assert!(GrumpkinBabyGiant::try_new(1).is_ok());
+        assert!(std::panic::catch_unwind(|| GrumpkinBabyGiant::new(0)).is_err());
+        assert_eq!(grumpkin_bsgs(grumpkin_scalar_base(5), 0), 0);
+    }
+
+    #[test]
+    fn grumpkin_run_canonical() {
+        let target = grumpkin_scalar_base(500);
+        for (key_mode, verify_results) in [
+            (KeyMode::X, true),
+            (KeyMode::X, false),
+            (KeyMode::XParity, true),
+        ] {
+            let mut grumpy_bsgs = GrumpkinBabyGiant::new(64)
+                .with_key_mode(key_mode)
+                .with_verify_results(verify_results);
+            assert_eq!(grumpy_bsgs.run_canonical(g(), target), Some((500, false)));
+            assert_eq!(grumpy_bsgs.run_canonical(g(), -target), Some((500, true)));
+            assert_eq!(grumpy_bsgs.run_canonical(g(), hash_to_grumpkin(b"x")), None);
+        }
+    }
+
+    #[test]
+    fn grumpkin_run_any_base() {
+        let h = hash_to_grumpkin(b"second base");
+        let target = (h * Fr::from(777_u64)).into();
+
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        assert_eq!(grumpy_bsgs.run_any_base(&[g(), h], target), Some((1, 777)));
+        assert_eq!(grumpy_bsgs.run_any_base(&[g()], target), None);
+    }
+
+    /// Naive log by repeated addition, to cross-check the solver
+    fn brute_force(base: Affine, target: Affine, max: u64) -> Option<u64> {
+        let mut current = Projective::zero();
+        for i in 0..max {
+            if current == target {
+                return Some(i);
+            }
+            current += base;
+        }
+        None
+    }
+
+    #[test]
+    fn run_matches_brute_force() {
+        let steps_count = 32;
+        let max_scalar = steps_count * steps_count;
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(steps_count);
+        grumpy_bsgs.baby_steps(&g());
+
+        // Boundaries of the table and the walk, then LCG spread scalars
+        let mut scalars = vec![0, 1, 31, 32, 33, 991, 992, 1023];
+        let mut seed = 777_u64;
+        for _ in 0..100 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            scalars.push((seed >> 33) % (max_scalar + 200));
+        }
+
+        for x in scalars {
+            let target = grumpkin_scalar_base(x);
+            let expected = brute_force(g(), target, max_scalar);
+            assert_eq!(grumpy_bsgs.solve(&g(), target), expected, "scalar {x}");
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn run_recovers_any_scalar_in_range(
+            (steps_count, x) in (1_u64..=64).prop_flat_map(|m| (Just(m), 0..m * m))
+        ) {
+            let mut grumpy_bsgs = GrumpkinBabyGiant::new(steps_count);
+            let target = grumpkin_scalar_base(x);
+
+            prop_assert_eq!(grumpy_bsgs.run(g(), target), Some(x));
+            prop_assert!(grumpy_bsgs.verify_batch(&g(), &[(target, x)]));
+            let cert = grumpy_bsgs.certificate(&g(), &target);
+            prop_assert!(cert.is_some_and(|cert| verify_certificate(&g(), &target, &cert)));
+        }
+    }
+
+    #[test]
+    fn grumpkin_expected_giant_steps() {
+        let (max_scalar, solves) = (64 * 64, 500_u64);
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+
+        // Deterministic LCG spreading scalars over 0..max_scalar
+        let mut seed = 12345_u64;
+        let mut total = 0;
+        for _ in 0..solves {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let x = (seed >> 33) % max_scalar;
+            assert_eq!(grumpy_bsgs.run(g(), grumpkin_scalar_base(x)), Some(x));
+            total += grumpy_bsgs.last_giant_steps().unwrap();
+        }
+
+        let average = total as f64 / solves as f64;
+        let expected = grumpy_bsgs.expected_giant_steps(max_scalar);
+        assert_eq!(expected, 32.0);
+        assert!((average - expected).abs() < 3.0, "average {average}");
+    }
+
+    #[test]
+    fn grumpkin_run_instances() {
+        let h = hash_to_grumpkin(b"second base");
+        let instances = [
+            (g(), grumpkin_scalar_base(1000), 64),
+            (h, (h * Fr::from(2024_u64)).into(), 128),
+            (h, grumpkin_scalar_base(5), 16),
+            (g(), g(), 0),
+        ];
+        assert_eq!(
+            run_instances(&instances),
+            vec![Some(1000), Some(2024), None, None]
+        );
+    }
+
+    #[test]
+    fn grumpkin_baby_steps_range_merge() {
+        let mut full = GrumpkinBabyGiant::new(32);
+        full.baby_steps(&g());
+
+        let mut lower = GrumpkinBabyGiant::new(32);
+        lower.baby_steps_range(&g(), 0, 16);
+        let mut upper = GrumpkinBabyGiant::new(32);
+        upper.baby_steps_range(&g(), 16, 32);
+        assert_eq!(upper.get_baby_steps().len(), 16);
+
+        lower.merge(&upper);
+        assert_eq!(lower.get_baby_steps(), full.get_baby_steps());
+        for x_num in [0_u64, 15, 16, 31, 500, 1023] {
+            assert_eq!(lower.solve(&g(), grumpkin_scalar_base(x_num)), Some(x_num));
+        }
+    }
+
+    #[test]
+    fn grumpkin_diagnose() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(16);
+        let target = grumpkin_scalar_base(1000);
+        let off_curve = Affine::new_unchecked(Fq::from(1), Fq::from(2));
+
+        assert_eq!(
+            grumpy_bsgs.diagnose(&g(), &off_curve),
+            FailureReason::OffCurve
+        );
+        assert_eq!(
+            grumpy_bsgs.diagnose(&off_curve, &target),
+            FailureReason::OffCurve
+        );
+        assert_eq!(
+            grumpy_bsgs.diagnose(&Affine::identity(), &target),
+            FailureReason::SmallOrderBase
+        );
+        // No table for g yet
+        assert_eq!(grumpy_bsgs.diagnose(&g(), &target), FailureReason::Unknown);
+
+        grumpy_bsgs.baby_steps(&g());
+        assert_eq!(
+            grumpy_bsgs.diagnose(&g(), &target),
+            FailureReason::OutOfRange
+        );
+        let in_range = grumpkin_scalar_base(200);
+        assert_eq!(
+            grumpy_bsgs.diagnose(&g(), &in_range),
+            FailureReason::Solvable
+        );
+        // Table for another base
+        let other = hash_to_grumpkin(b"other base");
+        assert_eq!(
+            grumpy_bsgs.diagnose(&other, &in_range),
+            FailureReason::Unknown
+        );
+    }
+
+    #[test]
+    fn grumpkin_giant_step_iterator() {
+        let target = grumpkin_scalar_base(1000);
+        let jump = -grumpkin_scalar_base(64);
+
+        let walk: Vec<_> = GiantStepIterator::new(target, jump, 20).collect();
+        assert_eq!(walk.len(), 20);
+        for (n, current) in walk {
+            assert_eq!(current, target + jump * Fr::from(n));
+        }
+        assert_eq!(GiantStepIterator::new(target, jump, 0).next(), None);
+    }
+
+    #[test]
+    fn grumpkin_run_with_offset() {
+        let offset = 1 << 40;
+        let target = grumpkin_scalar_base(offset + 1234);
+
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        assert_eq!(grumpy_bsgs.run_with_offset(g(), target, offset), Some(1234));
+        assert_eq!(grumpy_bsgs.run(g(), target), None);
+        assert_eq!(grumpy_bsgs.run_with_offset(g(), target, 0), None);
+    }
+
+    #[test]
+    fn grumpkin_run_as() {
+        let target = grumpkin_scalar_base(4321);
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(128);
+
+        assert_eq!(grumpy_bsgs.run_as::<u64>(g(), target), Some(4321));
+        assert_eq!(grumpy_bsgs.run_as::<u128>(g(), target), Some(4321));
+        assert_eq!(grumpy_bsgs.run_as::<Fr>(g(), target), Some(Fr::from(4321)));
+        assert_eq!(grumpy_bsgs.run_as::<Fr>(g(), hash_to_grumpkin(b"x")), None);
+    }
+
+    #[test]
+    fn grumpkin_run_with_progress() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64).with_progress_interval(10);
+        let mut reports = Vec::new();
+        let res = grumpy_bsgs.run_with_progress(g(), grumpkin_scalar_base(3000), |progress| {
+            reports.push(progress)
+        });
+        assert_eq!(res, Some(3000));
+
+        // 3000 matches at giant step 46
+        let steps: Vec<u64> = reports.iter().map(|p| p.giant_step).collect();
+        assert_eq!(steps, [0, 10, 20, 30, 40]);
+        assert!(reports.iter().all(|p| p.steps_count == 64));
+        assert!(reports.windows(2).all(|w| w[0].elapsed <= w[1].elapsed));
+        assert_eq!(reports[0].elapsed.is_some(), cfg!(feature = "timer"));
+
+        // Disabled by default
+        let mut quiet = GrumpkinBabyGiant::new(64);
+        let res = quiet.run_with_progress(g(), grumpkin_scalar_base(3000), |_| panic!());
+        assert_eq!(res, Some(3000));
+    }
+
+    #[test]
+    fn grumpkin_bidirectional() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64).with_bidirectional(true);
+        for x_num in [0_u64, 5, 64, 2047, 2048, 4032, 4037, 4095] {
+            assert_eq!(
+                grumpy_bsgs.run(g(), grumpkin_scalar_base(x_num)),
+                Some(x_num)
+            );
+        }
+        assert_eq!(grumpy_bsgs.run(g(), grumpkin_scalar_base(4096)), None);
+
+        // Near the top the forward walk scans every giant step first
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(1024);
+        grumpy_bsgs.baby_steps(&g());
+        let target = grumpkin_scalar_base((1 << 20) - 7);
+
+        assert_eq!(grumpy_bsgs.solve(&g(), target), Some((1 << 20) - 7));
+        assert_eq!(grumpy_bsgs.last_giant_lookups(), 1024);
+
+        grumpy_bsgs.bidirectional = true;
+        assert_eq!(grumpy_bsgs.solve(&g(), target), Some((1 << 20) - 7));
+        assert_eq!(grumpy_bsgs.last_giant_lookups(), 2);
+    }
+
+    #[test]
+    fn grumpkin_verify_table() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(16);
+        grumpy_bsgs.baby_steps(&g());
+        assert!(grumpy_bsgs.verify_table(&g(), 4));
+        assert!(grumpy_bsgs.verify_table(&g(), 16));
+        assert!(!grumpy_bsgs.verify_table(&hash_to_grumpkin(b"other"), 16));
+
+        let key = fq_key(&grumpkin_scalar_base(7));
+        grumpy_bsgs.baby_steps.insert(key, 8);
+        assert!(!grumpy_bsgs.verify_table(&g(), 16));
+        assert!(!grumpy_bsgs.verify_table(&g(), 1000));
+
+        let mut strided = GrumpkinBabyGiant::new(64).with_stride(4);
+        strided.baby_steps(&g());
+        assert!(strided.verify_table(&g(), 8));
+        assert!(strided.verify_table(&g(), 16));
+    }
+
+    #[test]
+    fn grumpkin_run_with_step() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        let jump = grumpy_bsgs.giant_step_jump(&g());
+        let steps = std::cell::Cell::new(0);
+        let step_fn = |current: &Affine| {
+            steps.set(steps.get() + 1);
+            (*current + jump).into()
+        };
+
+        for x_num in [0_u64, 63, 64, 1000, 4095, 4096] {
+            let target = grumpkin_scalar_base(x_num);
+            let expected = grumpy_bsgs.run(g(), target);
+            assert_eq!(grumpy_bsgs.run_with_step(g(), target, step_fn), expected);
+        }
+        assert!(steps.get() > 0);
+    }
+
+    #[test]
+    fn grumpkin_decompose_scalar() {
+        for direction in [Direction::Forward, Direction::Backward] {
+            let grumpy_bsgs = GrumpkinBabyGiant::new(64).with_direction(direction);
+            for scalar in [0_u64, 1, 63, 64, 1000, 4095] {
+                let (giant, baby) = grumpy_bsgs.decompose_scalar(scalar);
+                assert!(giant < 64 && baby < 64);
+                assert_eq!(grumpy_bsgs.process_result(&baby, &giant), scalar);
+            }
+        }
+        assert_eq!(GrumpkinBabyGiant::new(64).decompose_scalar(1000), (15, 40));
+    }
+
+    #[test]
+    fn grumpkin_batched_baby_steps() {
+        // Spans several batches with a partial last one
+        for (steps_count, stride) in [(600_u64, 1_u64), (1000, 3), (5, 1)] {
+            let mut grumpy_bsgs = GrumpkinBabyGiant::new(steps_count).with_stride(stride);
+            grumpy_bsgs.baby_steps(&g());
+
+            let naive: HashMap<FqKey, u64, TableHasher> = (0..steps_count)
+                .step_by(stride as usize)
+                .map(|k| (fq_key(&grumpkin_scalar_base(k)), k))
+                .collect();
+            assert_eq!(grumpy_bsgs.get_baby_steps(), &naive);
+        }
+
+        let mut ranged = GrumpkinBabyGiant::new(1000).with_stride(3);
+        ranged.baby_steps_range(&g(), 301, 700);
+        assert_eq!(ranged.get_baby_steps().len(), 133);
+        assert_eq!(
+            ranged.get_baby_steps()[&fq_key(&grumpkin_scalar_base(699))],
+            699
+        );
+    }
+
+    #[test]
+    fn grumpkin_giant_step_probe() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        grumpy_bsgs.baby_steps(&g());
+
+        // 1000 = 15·64 + 40, the 15th probe lands on baby step 40
+        let mut current = grumpkin_scalar_base(1000);
+        for probe in 1..=15 {
+            let (next, hit) = grumpy_bsgs.giant_step_probe(&current);
+            assert_eq!(next, grumpkin_scalar_base(1000 - probe * 64));
+            assert_eq!(hit, (probe == 15).then_some(40));
+            current = next;
+        }
+    }
+
+    #[test]
+    fn grumpkin_run_sorted_sizes() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(1);
+        let requests = [
+            (grumpkin_scalar_base(3000), 64),
+            (grumpkin_scalar_base(200), 16),
+            // Out of reach at its own size, even though the table grows later
+            (grumpkin_scalar_base(300), 16),
+        ];
+        assert_eq!(
+            grumpy_bsgs.run_sorted_sizes(g(), &requests),
+            vec![Some(3000), Some(200), None]
+        );
+        assert_eq!(grumpy_bsgs.get_baby_steps().len(), 64);
+        assert!(grumpy_bsgs.verify_table(&g(), 64));
+
+        // Growing matches a fresh build
+        let mut grown = GrumpkinBabyGiant::new(16).with_stride(3);
+        grown.baby_steps(&g());
+        grown.grow_baby_steps(&g(), 100);
+        let mut fresh = GrumpkinBabyGiant::new(100).with_stride(3);
+        fresh.baby_steps(&g());
+        assert_eq!(grown.get_baby_steps(), fresh.get_baby_steps());
+    }
+
+    #[test]
+    fn grumpkin_run_difference() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        let (t1, t2) = (
+            grumpkin_scalar_base(1 << 40),
+            grumpkin_scalar_base((1 << 40) + 1234),
+        );
+
+        assert_eq!(grumpy_bsgs.run_difference(g(), t1, t2), Some(1234));
+        assert_eq!(grumpy_bsgs.run_difference(g(), t2, t1), Some(-1234));
+        assert_eq!(grumpy_bsgs.run_difference(g(), t1, t1), Some(0));
+        assert_eq!(grumpy_bsgs.run_difference(g(), g(), t1), None);
+    }
+
+    #[test]
+    fn grumpkin_multi_base() {
+        let h = hash_to_grumpkin(b"second base");
+        let multi = MultiBaseGrumpkinBabyGiant::new(64, &[g(), h]);
+
+        assert_eq!(multi.run_multi(grumpkin_scalar_base(99_u64)), Some((0, 99)));
+        assert_eq!(
+            multi.run_multi((h * Fr::from(1234_u64)).into()),
+            Some((1, 1234))
+        );
+        assert_eq!(
+            multi.run_multi((h * Fr::from(64_u64)).into()),
+            Some((1, 64))
+        );
+        assert_eq!(multi.run_multi(hash_to_grumpkin(b"neither")), None);
+        // Shares 5·g's x-coordinate but isn't in range of either base
+        assert_eq!(multi.run_multi(-grumpkin_scalar_base(5)), None);
+
+        // -g shares every x-coordinate with g, both bases' entries are kept
+        let mirrored = MultiBaseGrumpkinBabyGiant::new(64, &[g(), -g()]);
+        assert_eq!(mirrored.run_multi(grumpkin_scalar_base(5)), Some((0, 5)));
+        assert_eq!(mirrored.run_multi(-grumpkin_scalar_base(5)), Some((1, 5)));
+        assert_eq!(
+            mirrored.run_multi(-grumpkin_scalar_base(300)),
+            Some((1, 300))
+        );
+        assert_eq!(mirrored.run_multi(Affine::identity()), Some((0, 0)));
+    }
+
+    #[test]
+    fn grumpkin_run_linear_combo() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        let a: Affine = grumpkin_scalar_base(1200_u64);
+        let b: Affine = grumpkin_scalar_base(345_u64);
+
+        assert_eq!(grumpy_bsgs.run_linear_combo(g(), &[a, b]), Some(1545));
+        assert_eq!(grumpy_bsgs.run_linear_combo(g(), &[]), Some(0));
+
+        let out_of_range: Affine = grumpkin_scalar_base(5000_u64);
+        assert_eq!(grumpy_bsgs.run_linear_combo(g(), &[a, out_of_range]), None);
+    }
+
+    #[test]
+    fn grumpkin_verify_batch() {
+        let grumpy_bsgs = GrumpkinBabyGiant::new(16);
+        let mut pairs: Vec<(Affine, u64)> = [3_u64, 77, 1 << 20, 4294967295]
+            .iter()
+            .map(|&x| (grumpkin_scalar_base(x), x))
+            .collect();
+
+        assert!(grumpy_bsgs.verify_batch(&g(), &pairs));
+        assert!(grumpy_bsgs.verify_batch(&g(), &[]));
+
+        pairs[2].1 += 1;
+        assert!(!grumpy_bsgs.verify_batch(&g(), &pairs));
+    }
+
+    #[test]
+    fn grumpkin_group_order() {
+        let order = grumpkin_order();
+        assert_eq!(order, BigInt::from_str(GRUMPKIN_ORDER).unwrap());
+        assert_eq!(order_bits(), 254);
+
+        assert!(g().mul_bigint(order).into_affine().is_zero());
+        let mut order_minus_one = order;
+        order_minus_one.sub_with_borrow(&BigInt::from(1_u64));
+        assert_eq!(g().mul_bigint(order_minus_one), -g().into_group());
+    }
+
+    #[test]
+    fn grumpkin_canonical_lookup() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(16);
+        grumpy_bsgs.baby_steps(&g());
+
+        // Identity carrying leftover coordinates
+        let identity = Affine {
+            x: Fq::from(5_u64),
+            y: Fq::from(7_u64),
+            infinity: true,
+        };
+        assert_eq!(grumpy_bsgs.canonical(&identity), Affine::identity());
+        assert_eq!(grumpy_bsgs.in_baby_steps(&identity), Some(0));
+        assert_eq!(grumpy_bsgs.solve(&g(), identity), Some(0));
+
+        // Point built through the projective group
+        let p: Affine = (g().into_group() * Fr::from(9_u64)).into_affine();
+        assert_eq!(grumpy_bsgs.in_baby_steps(&p), Some(9));
+    }
+
+    #[test]
+    fn grumpkin_run_escalating() {
+        // 5000 needs steps_count > 70, 8 doubles up to 128
+        let target: Affine = grumpkin_scalar_base(5000_u64);
+
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(8);
+        assert_eq!(grumpy_bsgs.run_escalating(g(), target, 1024), Ok(5000));
+        assert_eq!(grumpy_bsgs.steps_count(), 128);
+
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(8);
+        assert_eq!(
+            grumpy_bsgs.run_escalating(g(), target, 50),
+            Err(BsgsError::OrderExceeded)
+        );
+        assert_eq!(grumpy_bsgs.steps_count(), 50);
+    }
+
+    #[test]
+    fn grumpkin_x_collisions() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        grumpy_bsgs.baby_steps(&g());
+
+        // Brute force over all pairs of distinct points in the walk
+        let points: Vec<Affine> = (0..64_u64).map(grumpkin_scalar_base).collect();
+        let mut brute_force = 0;
+        for (i, p) in points.iter().enumerate() {
+            brute_force += points[..i].iter().filter(|q| q.x == p.x && *q != p).count();
+        }
+
+        assert_eq!(grumpy_bsgs.x_collisions(), brute_force);
+        assert_eq!(grumpy_bsgs.get_baby_steps().len() + brute_force, 64);
+    }
+
+    #[test]
+    fn grumpkin_collision_policy() {
+        let key = [1; 32];
+        let stored = |policy| {
+            let mut grumpy_bsgs = GrumpkinBabyGiant::new(16).with_collision_policy(policy);
+            assert_eq!(grumpy_bsgs.insert_baby_step(key, 7), None);
+            assert_eq!(grumpy_bsgs.insert_baby_step(key, 3), Some(7));
+            let extra = grumpy_bsgs.collided.get(&key).cloned().unwrap_or_default();
+            (grumpy_bsgs.get_baby_steps()[&key], extra)
+        };
+        assert_eq!(stored(CollisionPolicy::KeepFirst), (7, vec![]));
+        assert_eq!(stored(CollisionPolicy::KeepSmallest), (3, vec![]));
+        assert_eq!(stored(CollisionPolicy::StoreBoth), (7, vec![3]));
+
+        // StoreBoth lookups recompute candidates to find the right index
+        let mut grumpy_bsgs =
+            GrumpkinBabyGiant::new(16).with_collision_policy(CollisionPolicy::StoreBoth);
+        grumpy_bsgs.baby_steps(&g());
+        let key = fq_key(&grumpkin_scalar_base(7));
+        grumpy_bsgs.baby_steps.insert(key, 2);
+        grumpy_bsgs.insert_baby_step(key, 7);
+        assert_eq!(grumpy_bsgs.in_baby_steps(&grumpkin_scalar_base(7)), Some(7));
+    }
+
+    #[test]
+    fn grumpkin_same_subgroup() {
+        // Prime order, any non-identity base generates the whole group
+        let grumpy_bsgs = GrumpkinBabyGiant::new(16);
+        assert!(grumpy_bsgs.same_subgroup(&g(), &hash_to_grumpkin(b"any")));
+        assert!(!grumpy_bsgs.same_subgroup(&Affine::identity(), &g()));
+    }
+
+    #[test]
+    fn grumpkin_clear_cofactor_is_noop() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(16);
+        let target: Affine = grumpkin_scalar_base(99_u64);
+
+        assert_eq!(grumpy_bsgs.clear_cofactor(&target), target);
+        assert_eq!(grumpy_bsgs.run_cleared(g(), target), Some(99));
+    }
+
+    #[test]
+    fn grumpkin_solve_stream() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(32);
+        let targets = [5_u64, 999, 5000].map(grumpkin_scalar_base);
+
+        assert_eq!(
+            grumpy_bsgs
+                .solve_stream(targets.into_iter())
+                .collect::<Vec<_>>(),
+            vec![None, None, None]
+        );
+
+        grumpy_bsgs.baby_steps(&g());
+        let results: Vec<_> = grumpy_bsgs.solve_stream(targets.into_iter()).collect();
+        assert_eq!(results, vec![Some(5), Some(999), None]);
+    }
+
+    #[test]
+    fn grumpkin_run_nearest() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(16);
+
+        // 256 is one step past the last table combination 15·16 + 15
+        let target: Affine = grumpkin_scalar_base(256_u64);
+        assert_eq!(grumpy_bsgs.run(g(), target), None);
+        assert_eq!(grumpy_bsgs.run_nearest(g(), target, 0), None);
+        assert_eq!(grumpy_bsgs.run_nearest(g(), target, 1), Some((255, 1)));
+        assert_eq!(grumpy_bsgs.run_nearest(g(), target, 3), Some((255, 1)));
+
+        // -g is one addition short of the identity at 0, parity keeps it apart from g
+        let mut parity_bsgs = GrumpkinBabyGiant::new(16).with_key_mode(KeyMode::XParity);
+        let target = -g();
+        assert_eq!(parity_bsgs.run_nearest(g(), target, 1), Some((0, -1)));
+
+        // Exact matches are returned as is
+        let target: Affine = grumpkin_scalar_base(100_u64);
+        assert_eq!(grumpy_bsgs.run_nearest(g(), target, 1), Some((100, 0)));
+    }
+
+    #[test]
+    fn grumpkin_write_csv() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(16);
+        grumpy_bsgs.baby_steps(&g());
+
+        let mut csv = Vec::new();
+        grumpy_bsgs.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("index,x_coordinate"));
+        let rows: Vec<_> = lines.collect();
+        assert_eq!(rows.len(), 16);
+        for (i, row) in rows.iter().enumerate() {
+            let (index, x) = row.split_once(',').unwrap();
+            assert_eq!(index, i.to_string());
+            let point: Affine = grumpkin_scalar_base(i as u64);
+            assert_eq!(x, point.x.to_string());
+        }
+    }
+
+    #[test]
+    fn grumpkin_result_cache() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64).with_result_cache();
+        let target: Affine = grumpkin_scalar_base(777_u64);
+
+        assert_eq!(grumpy_bsgs.run(g(), target), Some(777));
+        assert_eq!(grumpy_bsgs.cache_hits(), 0);
+        assert_eq!(grumpy_bsgs.run(g(), target), Some(777));
+        assert_eq!(grumpy_bsgs.cache_hits(), 1);
+
+        // Negated target shares the x-coordinate but isn't a cache hit
+        assert_eq!(grumpy_bsgs.run(g(), -target), None);
+        assert_eq!(grumpy_bsgs.cache_hits(), 1);
+
+        // Cache is off by default
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        grumpy_bsgs.run(g(), target);
+        grumpy_bsgs.run(g(), target);
+        assert_eq!(grumpy_bsgs.cache_hits(), 0);
+    }
+
+    #[test]
+    fn grumpkin_scalar_mul() {
+        let grumpy_bsgs = GrumpkinBabyGiant::new(16);
+        for k in [0_u64, 1, 2, 7, 65536, u64::MAX] {
+            let expected: Affine = grumpkin_scalar_base(k);
+            assert_eq!(grumpy_bsgs.scalar_mul(&g(), k), expected);
+        }
+    }
+
+    #[test]
+    fn grumpkin_checkpoints() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        // 3000 = 46·64 + 56
+        let target: Affine = grumpkin_scalar_base(3000_u64);
+
+        let checkpoint = grumpy_bsgs
+            .run_from_checkpoint(g(), target, 0, 30)
+            .unwrap_err();
+        assert_eq!(checkpoint.giant_step, 30);
+
+        let res = grumpy_bsgs.resume(&g(), checkpoint, 30);
+        assert_eq!(res, Ok(Some(3000)));
+
+        let res = grumpy_bsgs.run_from_checkpoint(g(), target, 40, 100);
+        assert_eq!(res, Ok(Some(3000)));
+
+        let target: Affine = grumpkin_scalar_base(4096_u64);
+        let res = grumpy_bsgs.run_from_checkpoint(g(), target, 60, 100);
+        assert_eq!(res, Ok(None));
+
+        // A frozen table for the same base is reused rather than rebuilt
+        grumpy_bsgs.freeze();
+        let res = grumpy_bsgs.run_from_checkpoint(g(), grumpkin_scalar_base(3000), 40, 100);
+        assert_eq!(res, Ok(Some(3000)));
+        assert!(grumpy_bsgs.get_baby_steps().is_empty());
+    }
+
+    #[test]
+    fn checkpoint_rebuilds_for_another_base() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        grumpy_bsgs.baby_steps(&g());
+
+        let base = hash_to_grumpkin(b"other base");
+        let target: Affine = (base * Fr::from(3000_u64)).into();
+        let res = grumpy_bsgs.run_from_checkpoint(base, target, 0, 100);
+        assert_eq!(res, Ok(Some(3000)));
+    }
+
+    #[test]
+    fn grumpkin_directions() {
+        let mut forward = GrumpkinBabyGiant::new(32);
+        let mut backward = GrumpkinBabyGiant::new(32).with_direction(Direction::Backward);
+
+        for x_num in [0_u64, 1, 31, 32, 33, 500, 1023] {
+            let target: Affine = grumpkin_scalar_base(x_num);
+            assert_eq!(forward.run(g(), target), Some(x_num));
+            assert_eq!(backward.run(g(), target), Some(x_num));
+        }
+    }
+
+    #[test]
+    fn grumpkin_run_typed() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        let target: Affine = grumpkin_scalar_base(321_u64);
+
+        let x = grumpy_bsgs.run_typed(g(), target).unwrap();
+        assert_eq!(x, GrumpkinScalar(321));
+        assert_eq!(u64::from(x), 321);
+        assert_eq!(Fr::from(x), Fr::from(321_u64));
+    }
+
+    #[test]
+    fn grumpkin_run_fr() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        let target: Affine = grumpkin_scalar_base(1234_u64);
+
+        let x = grumpy_bsgs.run_fr(g(), target).unwrap();
+        assert_eq!(x, Fr::from(1234_u64));
+        assert_eq!(g() * x, target);
+    }
+
+    #[test]
+    fn grumpkin_fq_key() {
+        let p: Affine = grumpkin_scalar_base(42_u64);
+        let same: Affine = (g() * Fr::from(40_u64) + g() * Fr::from(2_u64)).into();
+        let other: Affine = grumpkin_scalar_base(43_u64);
+
+        assert_eq!(fq_key(&p), fq_key(&same));
+        assert_ne!(fq_key(&p), fq_key(&other));
+    }
+
+    #[test]
+    fn grumpkin_parity_keys() {
+        let p: Affine = grumpkin_scalar_base(5_u64);
+        assert_ne!(fq_parity_key(&p), fq_parity_key(&-p));
+        assert_eq!(key_to_fq(&fq_parity_key(&p)), p.x);
+        assert_eq!(key_to_fq(&fq_parity_key(&-p)), p.x);
+
+        // x keying can't tell -P apart, parity keying can
+        let mut x_keyed = GrumpkinBabyGiant::new(16);
+        x_keyed.baby_steps(&g());
+        assert_eq!(x_keyed.in_baby_steps(&-p), Some(5));
+
+        let mut parity_keyed = GrumpkinBabyGiant::new(16).with_key_mode(KeyMode::XParity);
+        parity_keyed.baby_steps(&g());
+        assert_eq!(parity_keyed.in_baby_steps(&p), Some(5));
+        assert_eq!(parity_keyed.in_baby_steps(&-p), None);
+
+        let target: Affine = grumpkin_scalar_base(200_u64);
+        assert_eq!(parity_keyed.solve(&g(), target), Some(200));
+    }
+
+    #[test]
+    fn grumpkin_from_config() {
+        let config = BsgsConfig {
+            steps_count: 64,
+            stride: 4,
+            parallel: true,
+            progress_interval: 10,
+        };
+        let mut grumpy_bsgs = GrumpkinBabyGiant::from_config(config);
+        assert_eq!(grumpy_bsgs.config(), config);
+
+        let target: Affine = grumpkin_scalar_base(4000_u64);
+        assert_eq!(grumpy_bsgs.run(g(), target), Some(4000));
+        assert_eq!(grumpy_bsgs.get_baby_steps().len(), 16);
+
+        assert_eq!(
+            GrumpkinBabyGiant::new(65_536).config(),
+            BsgsConfig::default()
+        );
+    }
+
+    #[test]
+    fn grumpkin_stride() {
+        let mut dense = GrumpkinBabyGiant::new(64);
+        let mut sparse = GrumpkinBabyGiant::new(64).with_stride(2);
+        let mut sparse_backward = GrumpkinBabyGiant::new(64)
+            .with_stride(3)
+            .with_direction(Direction::Backward);
+
+        for x_num in [0_u64, 1, 2, 63, 64, 65, 1001, 4095] {
+            let target: Affine = grumpkin_scalar_base(x_num);
+            let expected = dense.run(g(), target);
+            assert_eq!(expected, Some(x_num));
+            assert_eq!(sparse.run(g(), target), expected);
+            assert_eq!(sparse_backward.run(g(), target), expected);
+        }
+        assert_eq!(sparse.get_baby_steps().len(), 32);
+    }
+
+    #[test]
+    fn grumpkin_approx_table_bytes() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        assert_eq!(grumpy_bsgs.approx_table_bytes(), 0);
+
+        grumpy_bsgs.baby_steps(&g());
+        assert!(grumpy_bsgs.approx_table_bytes() >= 64 * size_of::<(FqKey, u64)>());
+    }
+
+    #[test]
+    fn grumpkin_autotune() {
+        let max_scalar = 1 << 32;
+        for max_bytes in [1 << 12, 1 << 16, 1 << 20, 1 << 24] {
+            let config = autotune(max_scalar, max_bytes);
+            assert!(config.steps_count * config.steps_count > max_scalar);
+            let entries = config.steps_count.div_ceil(config.stride);
+            assert!(estimate_table_bytes(entries) <= max_bytes);
+        }
+
+        // Plenty of memory needs no stride
+        assert_eq!(autotune(max_scalar, usize::MAX).stride, 1);
+
+        // The estimate matches a built table
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(1000);
+        grumpy_bsgs.baby_steps(&g());
+        assert_eq!(grumpy_bsgs.approx_table_bytes(), estimate_table_bytes(1000));
+    }
+
+    #[test]
+    fn grumpkin_table_compaction() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(4096);
+        grumpy_bsgs.baby_steps(&g());
+
+        grumpy_bsgs.steps_count = 100;
+        grumpy_bsgs.baby_steps(&g());
+        let len = grumpy_bsgs.get_baby_steps().len();
+        assert_eq!(len, 100);
+        assert!(grumpy_bsgs.get_baby_steps().capacity() < 2 * len);
+    }
+
+    #[cfg(feature = "logging")]
+    #[test]
+    fn grumpkin_logging() {
+        use std::sync::Mutex;
+
+        static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+        struct Capture;
+        impl log::Log for Capture {
+            fn enabled(&self, _: &log::Metadata) -> bool {
+                true
+            }
+            fn log(&self, record: &log::Record) {
+                MESSAGES.lock().unwrap().push(record.args().to_string());
+            }
+            fn flush(&self) {}
+        }
+
+        log::set_logger(&Capture).unwrap();
+        log::set_max_level(log::LevelFilter::Debug);
+
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(12_345);
+        grumpy_bsgs.run(g(), grumpkin_scalar_base(42));
+
+        // Other tests log concurrently, only look for this run's messages
+        let messages = MESSAGES.lock().unwrap();
+        for expected in [
+            "bsgs: building 12345 baby steps",
+            "bsgs: built 12345 baby steps, 0 x collisions",
+            "bsgs: giant walk started",
+            "bsgs: giant walk ended, found: true",
+        ] {
+            assert!(messages.iter().any(|m| m == expected), "missing {expected}");
+        }
+    }
+
+    #[test]
+    fn grumpkin_bsgs_small_scalars() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(16);
+
+        for x_num in [0, 1, 15, 16, 17, 32, 255] {
+            let target = grumpkin_scalar_base(x_num);
+            assert_eq!(grumpy_bsgs.run(g(), target), Some(x_num));
+        }
+    }
+
+    #[test]
+    fn batched_baby_steps_match_default_walk() {
+        // GroupBabyGiant keeps the trait's default baby_steps walk
+        let mut default_walk = crate::GroupBabyGiant::<Affine>::new(700);
+        default_walk.baby_steps(&g());
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(700);
+        grumpy_bsgs.baby_steps(&g());
+
+        assert_eq!(grumpy_bsgs.get_baby_steps().len(), 700);
+        for k in [0_u64, 1, 255, 256, 257, 699, 700, 5000] {
+            let point = grumpkin_scalar_base(k);
+            assert_eq!(
+                grumpy_bsgs.in_baby_steps(&point),
+                default_walk.in_baby_steps(&point)
+            );
+        }
+    }
+
+    #[test]
+    fn step_schedule_front_loads_dense_region() {
+        // Scalar sits in giant step 90 of 100
+        let x_num = 90 * 100 + 7;
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(100);
+        assert_eq!(
+            grumpy_bsgs.run(g(), grumpkin_scalar_base(x_num)),
+            Some(x_num)
+        );
+        assert_eq!(grumpy_bsgs.last_giant_lookups(), 91);
+
+        let schedule = (80..100).chain(0..80).collect();
+        let mut scheduled = GrumpkinBabyGiant::new(100).with_step_schedule(schedule);
+        assert_eq!(scheduled.run(g(), grumpkin_scalar_base(x_num)), Some(x_num));
+        assert_eq!(scheduled.last_giant_lookups(), 11);
+
+        // Still covers the rest of the range, only later
+        assert_eq!(scheduled.run(g(), grumpkin_scalar_base(42)), Some(42));
+        assert_eq!(scheduled.last_giant_lookups(), 21);
+    }
+
+    #[test]
+    fn compute_jump_cancels_steps_count_bases() {
+        let grumpy_bsgs = GrumpkinBabyGiant::new(1000);
+        let jump = grumpy_bsgs.compute_jump(&g());
+        assert_eq!(
+            grumpy_bsgs.scalar_mul(&g(), 1000) + jump,
+            Affine::identity()
+        );
+        assert_eq!(jump, -grumpkin_scalar_base(1000));
+    }
+
+    #[test]
+    fn lookup_direct_small_scalar() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(16);
+        assert_eq!(grumpy_bsgs.lookup_direct(&grumpkin_scalar_base(5)), None);
+
+        grumpy_bsgs.baby_steps(&g());
+        assert_eq!(grumpy_bsgs.lookup_direct(&grumpkin_scalar_base(5)), Some(5));
+        assert_eq!(grumpy_bsgs.lookup_direct(&-grumpkin_scalar_base(5)), None);
+        assert_eq!(grumpy_bsgs.lookup_direct(&grumpkin_scalar_base(16)), None);
+    }
+
+    #[test]
+    fn debug_skips_table_contents() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(5000);
+        grumpy_bsgs.baby_steps(&g());
+
+        let debug = format!("{grumpy_bsgs:?}");
+        assert_eq!(
+            debug,
+            "GrumpkinBabyGiant { steps_count: 5000, baby_steps_len: 5000, .. }"
+        );
+    }
+
+    #[test]
+    fn build_async_then_solve() {
+        let handle = GrumpkinBabyGiant::new(1000).build_async(g());
+        let target = grumpkin_scalar_base(123_456);
+
+        let grumpy_bsgs = handle.join().unwrap();
+        assert_eq!(grumpy_bsgs.get_baby_steps().len(), 1000);
+        assert_eq!(grumpy_bsgs.solve(&g(), target), Some(123_456));
+    }
+
+    #[test]
+    fn run_u128_past_u64() {
+        // Huge steps_count kept cheap by the stride, the schedule goes straight to the
+        // right giant step so the result giant·m + baby is past u64::MAX
+        let (m, stride, giant) = (1_u64 << 40, 1_u64 << 30, 1_u64 << 30);
+        let x_num = ((giant as u128) << 40) + 3 * stride as u128;
+        let target = (g() * Fr::from(x_num)).into_affine();
+
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(m)
+            .with_stride(stride)
+            .with_step_schedule(vec![giant]);
+        assert!(x_num > u64::MAX as u128);
+        assert_eq!(grumpy_bsgs.run_u128(g(), target), Some(x_num));
+        assert_eq!(grumpy_bsgs.last_giant_steps(), Some(giant));
+    }
+
+    #[test]
+    fn balanced_steps_count_near_optimum() {
+        let max_scalar = 1_000_000_000_000;
+        assert_eq!(balanced_steps_count(max_scalar, 1, 1), 1_000_000);
+
+        // Baby steps twice as costly, optimum sqrt(max_scalar / 2) ≈ 707_106.8
+        let m = balanced_steps_count(max_scalar, 2, 1);
+        assert!(m.abs_diff(707_107) <= 1, "{m}");
+        let m = balanced_steps_count(max_scalar, 1, 2);
+        assert!(m.abs_diff(1_414_214) <= 1, "{m}");
+
+        assert_eq!(balanced_steps_count(0, 1, 1), 1);
+        assert_eq!(balanced_steps_count(100, 0, 1), 100);
+        assert_eq!(balanced_steps_count(100, 1, 0), 1);
+    }
+
+    #[test]
+    fn certificate_roundtrip() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(100);
+        grumpy_bsgs.baby_steps(&g());
+        let target = grumpkin_scalar_base(4_321);
+
+        let cert = grumpy_bsgs.certificate(&g(), &target).unwrap();
+        assert_eq!((cert.giant_index, cert.baby_index), (43, 21));
+        assert_eq!(cert.meeting_point, grumpkin_scalar_base(21));
+        assert!(verify_certificate(&g(), &target, &cert));
+
+        // Tampered or mismatched certificates fail
+        let wrong_target = grumpkin_scalar_base(4_322);
+        assert!(!verify_certificate(&g(), &wrong_target, &cert));
+        let wrong_index = SolveCertificate {
+            baby_index: 22,
+            ..cert
+        };
+        assert!(!verify_certificate(&g(), &target, &wrong_index));
+
+        assert_eq!(
+            grumpy_bsgs.certificate(&g(), &grumpkin_scalar_base(20_000)),
+            None
+        );
+    }
+
+    #[test]
+    fn descending_build_matches_ascending() {
+        let mut ascending = GrumpkinBabyGiant::new(300);
+        ascending.baby_steps(&g());
+        let mut descending = GrumpkinBabyGiant::new(300).with_descending(true);
+        descending.baby_steps(&g());
+        assert_eq!(descending.get_baby_steps(), ascending.get_baby_steps());
+
+        for x_num in [0, 1, 299, 300, 45_000, 89_999] {
+            let target = grumpkin_scalar_base(x_num);
+            assert_eq!(descending.solve(&g(), target), Some(x_num));
+        }
+
+        // Partial ranges and strides walk down the same multiples
+        let mut strided = GrumpkinBabyGiant::new(300).with_stride(7);
+        strided.baby_steps_range(&g(), 10, 200);
+        let mut strided_down = GrumpkinBabyGiant::new(300)
+            .with_stride(7)
+            .with_descending(true);
+        strided_down.baby_steps_range(&g(), 10, 200);
+        assert_eq!(strided_down.get_baby_steps(), strided.get_baby_steps());
+    }
+
+    #[test]
+    fn run_scaled_base_recovers_k() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        let target = grumpkin_scalar_base(3 * 1000);
+
+        assert_eq!(grumpy_bsgs.run_scaled_base(g(), target, 3), Some(1000));
+        assert_eq!(grumpy_bsgs.run_scaled_base(g(), target, 1), Some(3000));
+        assert_eq!(grumpy_bsgs.run_scaled_base(g(), target, 0), None);
+    }
+
+    #[test]
+    fn run_borrowed_sorted_slice() {
+        let steps_count = 64;
+        let mut table: Vec<(Fq, u64)> = (0..steps_count)
+            .map(|k| (grumpkin_scalar_base(k).x, k))
+            .collect();
+        table.sort_unstable();
+        let jump = GrumpkinBabyGiant::new(steps_count).compute_jump(&g());
+
+        for x_num in [0, 1, 63, 64, 1000, 4095] {
+            let target = grumpkin_scalar_base(x_num);
+            assert_eq!(
+                GrumpkinBabyGiant::run_borrowed(target, &table, &jump, steps_count),
+                Some(x_num)
+            );
+        }
+        let target = grumpkin_scalar_base(4096);
+        assert_eq!(
+            GrumpkinBabyGiant::run_borrowed(target, &table, &jump, steps_count),
+            None
+        );
+    }
+
+    #[test]
+    fn frozen_table_solves_identically() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(1000);
+        grumpy_bsgs.baby_steps(&g());
+        let live_bytes = grumpy_bsgs.approx_table_bytes();
+
+        grumpy_bsgs.freeze();
+        assert!(grumpy_bsgs.get_baby_steps().is_empty());
+        assert!(grumpy_bsgs.approx_table_bytes() < live_bytes);
+        assert!(format!("{grumpy_bsgs:?}").contains("baby_steps_len: 1000"));
+
+        for x_num in [0, 1, 999, 1000, 123_456, 999_999] {
+            let target = grumpkin_scalar_base(x_num);
+            assert_eq!(grumpy_bsgs.solve(&g(), target), Some(x_num));
+        }
+        assert_eq!(
+            grumpy_bsgs.solve(&g(), grumpkin_scalar_base(1_000_000)),
+            None
+        );
+        assert!(grumpy_bsgs.verify_table(&g(), 1000));
+    }
+
+    #[test]
+    fn out_of_range_baby_steps_rejected() {
+        // Parity keys, so -5·G reached later in the walk doesn't match either
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64).with_key_mode(KeyMode::XParity);
+        grumpy_bsgs.baby_steps(&g());
+
+        // Corrupt entry past the table, process_result never sees it
+        let key = grumpy_bsgs.key(&grumpkin_scalar_base(5));
+        grumpy_bsgs.baby_steps.insert(key, 70);
+        assert_eq!(grumpy_bsgs.in_baby_steps(&grumpkin_scalar_base(5)), None);
+        assert_eq!(grumpy_bsgs.solve(&g(), grumpkin_scalar_base(5)), None);
+
+        // Stride lookups stepping down past m defer to the next giant step
+        let mut strided = GrumpkinBabyGiant::new(10).with_stride(7);
+        strided.baby_steps(&g());
+        assert_eq!(strided.in_baby_steps(&grumpkin_scalar_base(10)), None);
+        assert_eq!(strided.solve(&g(), grumpkin_scalar_base(10)), Some(10));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "baby step 64 outside the table")]
+    fn process_result_asserts_baby_in_range() {
+        GrumpkinBabyGiant::new(64).process_result(&64, &0);
+    }
+
+    #[test]
+    fn run_ratio_of_logs() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        let ratio = |grumpy_bsgs: &mut GrumpkinBabyGiant, a, b| {
+            grumpy_bsgs.run_ratio(g(), grumpkin_scalar_base(a), grumpkin_scalar_base(b))
+        };
+
+        assert_eq!(ratio(&mut grumpy_bsgs, 1000, 8), Some(Fr::from(125)));
+        let seventh = ratio(&mut grumpy_bsgs, 3, 7).unwrap();
+        assert_eq!(seventh * Fr::from(7), Fr::from(3));
+
+        assert_eq!(ratio(&mut grumpy_bsgs, 3, 0), None);
+        assert_eq!(ratio(&mut grumpy_bsgs, 3, 5000), None);
+    }
+
+    #[test]
+    fn run_next_consecutive_targets() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        assert_eq!(grumpy_bsgs.run_next(0), None);
+
+        assert_eq!(grumpy_bsgs.run(g(), grumpkin_scalar_base(1000)), Some(1000));
+        let mut res = 1000;
+        for _ in 0..5 {
+            res = grumpy_bsgs.run_next(res).unwrap();
+        }
+        assert_eq!(res, 1005);
+        assert_eq!(
+            grumpy_bsgs.last_solved(),
+            Some((g(), grumpkin_scalar_base(1005), 1005))
+        );
+
+        // Not the last result
+        assert_eq!(grumpy_bsgs.run_next(1000), None);
+        assert_eq!(grumpy_bsgs.last_solved().unwrap().2, 1005);
+    }
+
+    #[test]
+    fn gpu_table_roundtrip() {
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(300);
+        grumpy_bsgs.baby_steps(&g());
+
+        let (bytes, record_size) = grumpy_bsgs.gpu_table();
+        assert_eq!(record_size, 40);
+        assert_eq!(bytes.len(), 300 * record_size);
+
+        let records: Vec<(FqKey, u64)> = bytes
+            .chunks_exact(record_size)
+            .map(|record| {
+                let (key, baby_step) = record.split_at(32);
+                (
+                    key.try_into().unwrap(),
+                    u64::from_le_bytes(baby_step.try_into().unwrap()),
+                )
+            })
+            .collect();
+        assert!(records.is_sorted_by_key(|(key, _)| *key));
+
+        let table: HashMap<FqKey, u64, TableHasher> = records.iter().copied().collect();
+        assert_eq!(&table, grumpy_bsgs.get_baby_steps());
+        let key = fq_key(&grumpkin_scalar_base(123));
+        let idx = records.binary_search_by_key(&key, |(key, _)| *key).unwrap();
+        assert_eq!(records[idx].1, 123);
+    }
+
+    #[test]
+    fn element_bytes_equal_for_equal_points() {
+        let grumpy_bsgs = GrumpkinBabyGiant::new(16).with_key_mode(KeyMode::XParity);
+        let p = grumpkin_scalar_base(77);
+        let same: Affine = (g() * Fr::from(7) * Fr::from(11)).into_affine();
+
+        assert_eq!(
+            grumpy_bsgs.element_bytes(&p),
+            grumpy_bsgs.element_bytes(&same)
+        );
+        assert_eq!(grumpy_bsgs.element_bytes(&p).len(), 32);
+        assert_ne!(
+            grumpy_bsgs.element_bytes(&p),
+            grumpy_bsgs.element_bytes(&grumpkin_scalar_base(78))
+        );
+
+        // Any point flagged at infinity is the identity
+        let mut flagged = p;
+        flagged.infinity = true;
+        assert_eq!(
+            grumpy_bsgs.element_bytes(&flagged),
+            grumpy_bsgs.element_bytes(&Affine::identity())
+        );
+    }
+
+    #[test]
+    fn kangaroo_solves_in_range() {
+        for x_num in [0, 1, 12_345, 65_535] {
+            let target = grumpkin_scalar_base(x_num);
+            assert_eq!(kangaroo(&g(), &target, 1 << 16, || true), Some(x_num));
+        }
+
+        let mut budget = 1000;
+        let target = grumpkin_scalar_base(1 << 40);
+        assert_eq!(
+            kangaroo(&g(), &target, 1 << 16, || {
+                budget -= 1;
+                budget > 0
+            }),
+            None
+        );
+    }
+
+    #[cfg(feature = "timer")]
+    #[test]
+    fn solve_within_time_switches_to_kangaroo() {
+        let budget = Duration::from_secs(30);
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(8);
+        assert_eq!(
+            grumpy_bsgs.solve_within_time(g(), grumpkin_scalar_base(50), budget),
+            Some(50)
+        );
+        assert_eq!(grumpy_bsgs.last_solve_mode(), Some(SolveMode::Bsgs));
+        // Beyond the 64 BSGS covers, within the 4096 the kangaroo does
+        assert_eq!(
+            grumpy_bsgs.solve_within_time(g(), grumpkin_scalar_base(3000), budget),
+            Some(3000)
+        );
+        assert_eq!(grumpy_bsgs.last_solve_mode(), Some(SolveMode::Kangaroo));
+
+        let budget = Duration::from_millis(50);
+        let res = grumpy_bsgs.solve_within_time(g(), hash_to_grumpkin(b"far away"), budget);
+        assert_eq!(res, None);
+        assert_eq!(grumpy_bsgs.last_solve_mode(), Some(SolveMode::Kangaroo));
+    }
+
+    #[test]
+    fn unreachable_scalars_past_steps_count_squared() {
+        let grumpy_bsgs = GrumpkinBabyGiant::new(16);
+        assert_eq!(grumpy_bsgs.max_solvable(), 255);
+        assert_eq!(grumpy_bsgs.unreachable_scalars(250..260), 256..260);
+        assert!(grumpy_bsgs.unreachable_scalars(0..256).is_empty());
+        assert_eq!(grumpy_bsgs.unreachable_scalars(0..u64::MAX), 256..u64::MAX);
+        assert_eq!(GrumpkinBabyGiant::new(1 << 32).max_solvable(), u64::MAX);
+
+        // Every listed scalar really fails, every other one solves
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(4);
+        let unreachable = grumpy_bsgs.unreachable_scalars(0..20);
+        for x_num in 0..20 {
+            let solved = grumpy_bsgs.run(g(), grumpkin_scalar_base(x_num));
+            assert_eq!(
+                solved.is_none(),
+                unreachable.contains(&x_num),
+                "scalar {x_num}"
+            );
+        }
+    }
+
+    #[test]
+    fn scalar_bytes_roundtrip() {
+        for s in [0, 1, 0xbeef, u64::MAX] {
+            assert_eq!(scalar_from_bytes(&scalar_to_bytes(s)), Some(s));
+        }
+        assert_eq!(scalar_to_bytes(0x0102), [0, 0, 0, 0, 0, 0, 1, 2]);
+        assert_eq!(scalar_from_bytes(&[1, 2]), None);
+        assert_eq!(scalar_from_bytes(&[0; 9]), None);
+    }
+
+    #[test]
+    fn run_file_skips_bad_lines() {
+        let line = |x_num| {
+            let point = grumpkin_scalar_base(x_num);
+            format!("{},{}", point.x, point.y)
+        };
+        let path = std::env::temp_dir().join(format!("bsgs-targets-{}", std::process::id()));
+        std::fs::write(
+            &path,
+            [line(1000), "12,not a number".into(), line(42)].join("\n"),
+        )
+        .unwrap();
+
+        let mut grumpy_bsgs = GrumpkinBabyGiant::new(64);
+        let results = grumpy_bsgs.run_file(g(), &path).unwrap();
+        assert_eq!(results, [Some(1000), None, Some(42)]);
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(grumpy_bsgs.run_file(g(), &path).is_err());
+    }
+
+    #[test]
+    fn both_hashers_solve() {
+        assert_eq!(GrumpkinBabyGiant::new(256).hasher(), TableHasher::Sip);
+        assert_eq!(
+            GrumpkinBabyGiant::new(FX_HASHER_STEPS).hasher(),
+            TableHasher::Fx
+        );
+
+        for hasher in [TableHasher::Sip, TableHasher::Fx] {
+            let mut grumpy_bsgs = GrumpkinBabyGiant::new(256).with_hasher(hasher);
+            for x_num in [0_u64, 1, 255, 256, 40_000, 65_535] {
+                assert_eq!(
+                    grumpy_bsgs.run(g(), grumpkin_scalar_base(x_num)),
+                    Some(x_num)
+                );
+            }
+            assert_eq!(grumpy_bsgs.hasher(), hasher);
+            assert_eq!(grumpy_bsgs.get_baby_steps().len(), 256);
+        }
+    }
+
+    #[test]
+    fn parallel_baby_steps_match_serial() {
+        let (forward, backward) = (Direction::Forward, Direction::Backward);
+        let (first, smallest, both) = (
+            CollisionPolicy::KeepFirst,
+            CollisionPolicy::KeepSmallest,
+            CollisionPolicy::StoreBoth,
+        );
+        for (steps_count, stride, direction, descending, policy) in [
+            (1000_u64, 1_u64, forward, false, smallest),
+            (1000, 3, forward, true, first),
+            (300, 1, backward, false, both),
+            (300, 7, backward, true, smallest),
+            (1, 1, forward, false, smallest),
+        ] {
+            let build = || {
+                GrumpkinBabyGiant::new(steps_count)
+                    .with_stride(stride)
+                    .with_direction(direction)
+                    .with_descending(descending)
+                    .with_collision_policy(policy)
+            };
+            let mut serial = build();
+            serial.baby_steps(&g());
+            let mut parallel = build();
+            parallel.baby_steps_parallel(&g());
+            // Forced shard counts and small windows, whatever the machine's cores
+            for shards in [1, 3, 4] {
+                let mut sharded = build();
+                sharded.baby_steps_sharded(&g(), shards, 7);
+                assert!(sharded == serial, "{shards} shards");
+            }
+
+            assert_eq!(parallel.get_baby_steps(), serial.get_baby_steps());
+            assert_eq!(parallel.x_collisions(), serial.x_collisions());
+            assert!(parallel == serial);
+            let x_num = parallel.max_solvable();
+            assert_eq!(
+                parallel.solve(&g(), grumpkin_scalar_base(x_num)),
+                Some(x_num)
+            );
+        }
+
+        // Every multiple of the identity collides, the policy and order pick the survivor
+        for (descending, policy) in [(true, first), (false, both)] {
+            let build = || {
+                GrumpkinBabyGiant::new(600)
+                    .with_descending(descending)
+                    .with_collision_policy(policy)
+            };
+            let mut serial = build();
+            serial.baby_steps(&Affine::identity());
+            let mut parallel = build();
+            parallel.baby_steps_parallel(&Affine::identity());
+            assert!(parallel == serial);
+            let mut sharded = build();
+            sharded.baby_steps_sharded(&Affine::identity(), 4, 7);
+            assert!(sharded == serial);
+        }
+    }
+
+    #[test]
+    fn verify_results_rejects_x_collisions() {
+        for verify_results in [true, false] {
+            let mut grumpy_bsgs = GrumpkinBabyGiant::new(64).with_verify_results(verify_results);
+            for x_num in [0_u64, 5, 64, 4095] {
+                assert_eq!(
+                    grumpy_bsgs.run(g(), grumpkin_scalar_base(x_num)),
+                    Some(x_num)
+                );
+            }
+        }
+
+        // -5·base shares 5·base's x-coordinate, only verification catches the wrong answer
+        let target = -grumpkin_scalar_base(5);
+        let mut unverified = GrumpkinBabyGiant::new(64).with_verify_results(false);
+        assert_eq!(unverified.run(g(), target), Some(5));
+        let mut verified = GrumpkinBabyGiant::new(64);
+        assert_eq!(verified.run(g(), target), None);
+        assert_eq!(verified.last_giant_lookups(), 64);
+    }
+
+    #[test]
+    fn sorted_keys_are_sorted_x_coordinates() {
+        for key_mode in [KeyMode::X, KeyMode::XParity] {
+            let mut grumpy_bsgs = GrumpkinBabyGiant::new(300).with_key_mode(key_mode);
+            grumpy_bsgs.baby_steps(&g());
+
+            let keys = grumpy_bsgs.sorted_keys();
+            assert_eq!(keys.len(), 300);
+            let bytes: Vec<FqKey> = keys
+                .iter()
+                .map(|x| {
+                    let mut key = [0; 32];
+                    x.serialize_compressed(&mut key[..]).unwrap();
+                    key
+                })
+                .collect();
+            assert!(bytes.is_sorted());
+            assert!(keys.contains(&grumpkin_scalar_base(123).x));
+
+            grumpy_bsgs.freeze();
+            assert_eq!(grumpy_bsgs.sorted_keys(), keys);
+        }
     }
 }
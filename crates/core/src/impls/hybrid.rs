@@ -0,0 +1,297 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::PathBuf,
+};
+
+use ark_grumpkin::{Affine, Fr};
+use memmap2::Mmap;
+
+use crate::{
+    BabyGiantOps,
+    impls::grumpkin::{FqKey, estimate_table_bytes, fq_key},
+};
+
+/// Spilled record, the key followed by the little endian baby step index
+const RECORD_LEN: usize = 32 + 8;
+
+/// Spilled records sorted in memory at once before going to disk as a run
+const RUN_RECORDS: usize = 1 << 16;
+
+/// Next record of a sorted run, None at its end
+fn read_record(reader: &mut impl Read) -> io::Result<Option<[u8; RECORD_LEN]>> {
+    let mut record = [0; RECORD_LEN];
+    match reader.read_exact(&mut record) {
+        Ok(()) => Ok(Some(record)),
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Grumpkin BSGS keeping the first baby steps in RAM within `ram_budget_bytes` and the
+/// rest in a memory-mapped file sorted by key, for tables that nearly fit in memory
+/// Lookups check the HashMap first, then binary search the file
+/// The file is external sorted, so building holds at most `run_records` spilled records
+/// in memory, plus one per run while merging
+pub struct HybridBabyGiant {
+    steps_count: u64,
+    ram_budget_bytes: usize,
+    run_records: usize,
+    spill_path: PathBuf,
+    ram: HashMap<FqKey, u64>,
+    spill: Option<Mmap>,
+}
+
+impl HybridBabyGiant {
+    pub fn new(steps_count: u64, ram_budget_bytes: usize, spill_path: impl Into<PathBuf>) -> Self {
+        Self {
+            steps_count,
+            ram_budget_bytes,
+            run_records: RUN_RECORDS,
+            spill_path: spill_path.into(),
+            ram: HashMap::new(),
+            spill: None,
+        }
+    }
+
+    /// Spilled records sorted in memory per run, fewer runs merge faster but use more RAM
+    pub fn with_run_records(mut self, run_records: usize) -> Self {
+        self.run_records = run_records.max(1);
+        self
+    }
+
+    /// Number of baby steps kept in RAM, the most whose table fits the budget
+    pub fn ram_entries(&self) -> u64 {
+        // estimate_table_bytes grows with entries, so binary search the largest fit
+        let (mut low, mut high) = (0, self.steps_count);
+        while low < high {
+            let mid = low + (high - low).div_ceil(2);
+            if estimate_table_bytes(mid) <= self.ram_budget_bytes {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        low
+    }
+
+    /// Number of baby steps in the spill file
+    pub fn spilled_entries(&self) -> usize {
+        self.spill
+            .as_ref()
+            .map_or(0, |spill| spill.len() / RECORD_LEN)
+    }
+
+    /// File holding sorted run number `run`, next to the spill file
+    fn run_path(&self, run: usize) -> PathBuf {
+        let mut path = self.spill_path.clone().into_os_string();
+        path.push(format!(".run{run}"));
+        path.into()
+    }
+
+    /// Sorts the buffered records and writes them out as the next run
+    fn write_run(
+        &self,
+        records: &mut Vec<[u8; RECORD_LEN]>,
+        runs: &mut Vec<PathBuf>,
+    ) -> io::Result<()> {
+        records.sort_unstable();
+        let path = self.run_path(runs.len());
+        let mut file = BufWriter::new(File::create(&path)?);
+        for record in records.iter() {
+            file.write_all(record)?;
+        }
+        file.flush()?;
+        records.clear();
+        runs.push(path);
+        Ok(())
+    }
+
+    /// Merges the sorted runs into the spill file, maps it and deletes the runs
+    fn merge_runs(&mut self, runs: Vec<PathBuf>) -> io::Result<()> {
+        self.spill = None;
+        if runs.is_empty() {
+            return Ok(());
+        }
+
+        let mut readers = runs
+            .iter()
+            .map(|path| File::open(path).map(BufReader::new))
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut heads = BinaryHeap::new();
+        for (run, reader) in readers.iter_mut().enumerate() {
+            if let Some(record) = read_record(reader)? {
+                heads.push(Reverse((record, run)));
+            }
+        }
+
+        let mut file = BufWriter::new(File::create(&self.spill_path)?);
+        while let Some(Reverse((record, run))) = heads.pop() {
+            file.write_all(&record)?;
+            if let Some(next) = read_record(&mut readers[run])? {
+                heads.push(Reverse((next, run)));
+            }
+        }
+        file.into_inner()?.sync_all()?;
+        for path in runs {
+            fs::remove_file(path)?;
+        }
+
+        // Safety: the file is only written above, before it's mapped
+        self.spill = Some(unsafe { Mmap::map(&File::open(&self.spill_path)?)? });
+        Ok(())
+    }
+
+    fn spill_lookup(&self, key: &FqKey) -> Option<u64> {
+        let spill = self.spill.as_ref()?;
+        let (mut low, mut high) = (0, spill.len() / RECORD_LEN);
+        while low < high {
+            let mid = (low + high) / 2;
+            let record = &spill[mid * RECORD_LEN..(mid + 1) * RECORD_LEN];
+            match record[..32].cmp(key) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => {
+                    return Some(u64::from_le_bytes(record[32..].try_into().unwrap()));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl BabyGiantOps for HybridBabyGiant {
+    type El = Affine;
+    type Scalar = u64;
+    type Key = FqKey;
+
+    fn steps_count(&self) -> u64 {
+        self.steps_count
+    }
+
+    fn key(&self, el: &Affine) -> FqKey {
+        fq_key(el)
+    }
+
+    /// Only ever lands in RAM, the spill file is written in bulk by `baby_steps`
+    fn store_baby_step(&mut self, key: FqKey, baby_step: u64) {
+        self.ram.entry(key).or_insert(baby_step);
+    }
+
+    fn clear_baby_steps(&mut self) {
+        self.ram.clear();
+        self.spill = None;
+    }
+
+    /// Panics if the spill file can't be written or mapped
+    fn baby_steps(&mut self, base: &Affine) {
+        let ram_entries = self.ram_entries();
+        self.ram = HashMap::with_capacity(ram_entries as usize);
+        let spilled = (self.steps_count - ram_entries) as usize;
+        let mut records = Vec::with_capacity(spilled.min(self.run_records));
+        let mut runs = Vec::new();
+
+        // Walk starts at the identity, entry k maps k·base
+        let mut current = Affine::identity();
+        for baby_step in 0..self.steps_count {
+            let key = self.key(&current);
+            if baby_step < ram_entries {
+                self.store_baby_step(key, baby_step);
+            } else {
+                let mut record = [0; RECORD_LEN];
+                record[..32].copy_from_slice(&key);
+                record[32..].copy_from_slice(&baby_step.to_le_bytes());
+                records.push(record);
+                if records.len() == self.run_records {
+                    self.write_run(&mut records, &mut runs)
+                        .expect("failed to spill baby steps");
+                }
+            }
+            current = (current + base).into();
+        }
+
+        if !records.is_empty() {
+            self.write_run(&mut records, &mut runs)
+                .expect("failed to spill baby steps");
+        }
+        self.merge_runs(runs).expect("failed to spill baby steps");
+    }
+
+    fn in_baby_steps(&self, target: &Affine) -> Option<u64> {
+        let key = self.key(target);
+        self.ram
+            .get(&key)
+            .copied()
+            .or_else(|| self.spill_lookup(&key))
+    }
+
+    fn identity(&self) -> Affine {
+        Affine::identity()
+    }
+
+    fn el_operation(&self, lhs: &Affine, rhs: &Affine) -> Affine {
+        (*lhs + *rhs).into()
+    }
+
+    fn scalar_mul(&self, el: &Affine, k: u64) -> Affine {
+        (*el * Fr::from(k)).into()
+    }
+
+    fn giant_step_jump(&self, base: &Affine) -> Affine {
+        -self.scalar_mul(base, self.steps_count)
+    }
+
+    fn process_result(&self, baby: &u64, giant: &u64) -> u64 {
+        giant * self.steps_count + baby
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::grumpkin::{g, grumpkin_scalar_base};
+
+    #[test]
+    fn hybrid_spills_and_solves() {
+        let path = std::env::temp_dir().join(format!("bsgs-spill-{}", std::process::id()));
+        let mut hybrid = HybridBabyGiant::new(64, 1000, &path);
+        hybrid.baby_steps(&g());
+
+        assert!(hybrid.ram_entries() < 64);
+        assert!(estimate_table_bytes(hybrid.ram_entries()) <= 1000);
+        assert_eq!(hybrid.ram_entries() as usize + hybrid.spilled_entries(), 64);
+
+        for x_num in [0_u64, 5, 40, 63, 64, 1000, 4095] {
+            assert_eq!(hybrid.run(g(), grumpkin_scalar_base(x_num)), Some(x_num));
+        }
+        assert_eq!(hybrid.run(g(), grumpkin_scalar_base(4096)), None);
+
+        // Everything fits, nothing spilled
+        let mut in_ram = HybridBabyGiant::new(64, usize::MAX, &path);
+        assert_eq!(in_ram.run(g(), grumpkin_scalar_base(1000)), Some(1000));
+        assert_eq!(in_ram.spilled_entries(), 0);
+
+        // Spill sorted in runs of 5 and merged, the runs are cleaned up
+        let merged_path = path.with_extension("merged");
+        let mut merged = HybridBabyGiant::new(64, 1000, &merged_path).with_run_records(5);
+        for x_num in [0_u64, 40, 63, 1000, 4095] {
+            assert_eq!(merged.run(g(), grumpkin_scalar_base(x_num)), Some(x_num));
+        }
+        assert_eq!(merged.spilled_entries(), hybrid.spilled_entries());
+        let spill = merged.spill.as_ref().unwrap();
+        assert!(spill.chunks(RECORD_LEN).is_sorted());
+        assert!(!merged.run_path(0).exists());
+
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(merged_path).unwrap();
+    }
+
+    #[test]
+    fn ram_entries_saturates() {
+        assert_eq!(estimate_table_bytes(u64::MAX), usize::MAX);
+        let hybrid = HybridBabyGiant::new(u64::MAX, usize::MAX / 2, "unused");
+        assert!(hybrid.ram_entries() < u64::MAX);
+    }
+}
@@ -2,10 +2,21 @@ use std::collections::HashMap;
 
 use crate::BabyGiantOps;
 
+/// Scalar solved in a `U128Field`, kept apart from curve scalars by its type
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct U128Scalar(pub u128);
+
+impl From<U128Scalar> for u128 {
+    fn from(s: U128Scalar) -> u128 {
+        s.0
+    }
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct U128Field {
     modulus: u128,
     steps_count: u128,
+    cofactor: u128,
     baby_steps: HashMap<u128, u128>,
 }
 
@@ -15,42 +26,94 @@ impl U128Field {
         Self {
             modulus,
             steps_count,
+            cofactor: 1,
             baby_steps: HashMap::new(),
         }
     }
+
+    /// Sets the cofactor of the prime-order subgroup, e.g. 2 for safe primes p = 2q + 1
+    pub fn with_cofactor(mut self, cofactor: u128) -> Self {
+        self.cofactor = cofactor;
+        self
+    }
+
+    /// Runs BSGS returning a typed `U128Scalar`
+    pub fn run_typed(&mut self, base: u128, target: u128) -> Option<U128Scalar> {
+        self.run(base, target).map(U128Scalar)
+    }
+
+    /// Solves for `x mod order` where `order` divides the group order `modulus - 1`
+    /// Base and target are projected into the order-`order` subgroup by raising them
+    /// to the cofactor, and the smaller DLP is solved there (building block for Pohlig-Hellman)
+    pub fn run_mod(&mut self, base: u128, target: u128, order: u128) -> Option<u128> {
+        let group_order = self.modulus - 1;
+        if order == 0 || !group_order.is_multiple_of(order) {
+            return None;
+        }
+
+        let cofactor = group_order / order;
+        let sub_base = mod_exp(base, cofactor, self.modulus);
+        let sub_target = mod_exp(target, cofactor, self.modulus);
+
+        // Solve in the subgroup with a table sized for its order
+        let mut sub_field = Self {
+            modulus: self.modulus,
+            steps_count: order.isqrt() + 1,
+            cofactor,
+            baby_steps: HashMap::new(),
+        };
+        sub_field.run(sub_base, sub_target).map(|res| res % order)
+    }
 }
 
 /// Implementation for u128 modular exponentiation
 impl BabyGiantOps for U128Field {
     type El = u128;
     type Scalar = u128;
+    type Key = u128;
 
     fn steps_count(&self) -> Self::Scalar {
         self.steps_count
     }
 
-    fn baby_steps(&mut self, base: &u128) {
-        let mut baby_steps = HashMap::new();
-        let mut current = *base;
+    fn key(&self, el: &u128) -> u128 {
+        *el
+    }
 
-        let mut baby_step = 0;
-        while baby_step < self.steps_count {
-            baby_step += 1;
-            baby_steps.insert(current, baby_step);
-            current = current * base % self.modulus;
-        }
+    fn store_baby_step(&mut self, key: u128, baby_step: u128) {
+        self.baby_steps.insert(key, baby_step);
+    }
 
-        self.baby_steps = baby_steps;
+    fn clear_baby_steps(&mut self) {
+        self.baby_steps.clear();
     }
 
-    fn in_baby_steps(&self, base: &u128) -> Option<&Self::Scalar> {
-        self.baby_steps.get(base)
+    fn in_baby_steps(&self, base: &u128) -> Option<Self::Scalar> {
+        self.baby_steps.get(base).copied()
+    }
+
+    fn identity(&self) -> u128 {
+        1
     }
 
     fn el_operation(&self, lhs: &u128, rhs: &u128) -> u128 {
         (lhs * rhs) % self.modulus
     }
 
+    fn clear_cofactor(&self, el: &u128) -> u128 {
+        mod_exp(*el, self.cofactor, self.modulus)
+    }
+
+    /// Also checks the other way round: base in the prime-order subgroup needs target there
+    fn same_subgroup(&self, base: &u128, target: &u128) -> bool {
+        let order = (self.modulus - 1) / self.cofactor;
+        let in_prime_subgroup = |el: &u128| mod_exp(*el, order, self.modulus) == 1;
+        let in_cofactor_subgroup = |el: &u128| self.clear_cofactor(el) == 1;
+
+        (!in_prime_subgroup(base) || in_prime_subgroup(target))
+            && (!in_cofactor_subgroup(base) || in_cofactor_subgroup(target))
+    }
+
     fn giant_step_jump(&self, base: &u128) -> u128 {
         mod_exp(*base, self.modulus - 1 - self.steps_count, self.modulus)
     }
@@ -82,9 +145,106 @@ fn mod_exp(base: u128, exponent: u128, modulus: u128) -> u128 {
     result
 }
 
+/// (x + y) mod modulus for x, y < modulus, without overflowing near u128::MAX
+fn add_mod(x: u128, y: u128, modulus: u128) -> u128 {
+    x.checked_sub(modulus - y).unwrap_or_else(|| x + y)
+}
+
+/// (x · y) mod modulus by double-and-add, no u128 product is ever formed
+fn mul_mod(x: u128, mut y: u128, modulus: u128) -> u128 {
+    let (mut result, mut doubling) = (0, x % modulus);
+    while y > 0 {
+        if y & 1 == 1 {
+            result = add_mod(result, doubling, modulus);
+        }
+        doubling = add_mod(doubling, doubling, modulus);
+        y >>= 1;
+    }
+    result
+}
+
+/// Inverse of a modulo `modulus` by the extended Euclidean algorithm
+/// None when a and modulus aren't coprime, not constant time
+pub fn mod_inverse(a: u128, modulus: u128) -> Option<u128> {
+    if modulus == 0 {
+        return None;
+    }
+
+    let (mut r0, mut r1) = (modulus, a % modulus);
+    // Coefficients of a, kept reduced mod modulus so they stay unsigned
+    let (mut t0, mut t1): (u128, u128) = (0, 1 % modulus);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        // t0 - q·t1 as a modular subtraction, safe for moduli up to u128::MAX
+        let qt = mul_mod(q % modulus, t1, modulus);
+        (t0, t1) = (
+            t1,
+            t0.checked_sub(qt).unwrap_or_else(|| t0 + (modulus - qt)),
+        );
+    }
+    (r0 == 1).then_some(t0)
+}
+
 #[cfg(test)]
 mod tests {
+    use std::cell::Cell;
+
     use super::*;
+    use crate::BsgsError;
+
+    /// Delegates to U128Field while counting group operations
+    struct CountingField {
+        field: U128Field,
+        ops: Cell<usize>,
+    }
+
+    impl BabyGiantOps for CountingField {
+        type El = u128;
+        type Scalar = u128;
+        type Key = u128;
+
+        fn steps_count(&self) -> u128 {
+            self.field.steps_count()
+        }
+
+        fn key(&self, el: &u128) -> u128 {
+            self.field.key(el)
+        }
+
+        fn store_baby_step(&mut self, key: u128, baby_step: u128) {
+            self.field.store_baby_step(key, baby_step)
+        }
+
+        fn clear_baby_steps(&mut self) {
+            self.field.clear_baby_steps()
+        }
+
+        fn baby_steps(&mut self, base: &u128) {
+            self.field.baby_steps(base)
+        }
+
+        fn in_baby_steps(&self, target: &u128) -> Option<u128> {
+            self.field.in_baby_steps(target)
+        }
+
+        fn identity(&self) -> u128 {
+            self.field.identity()
+        }
+
+        fn el_operation(&self, lhs: &u128, rhs: &u128) -> u128 {
+            self.ops.set(self.ops.get() + 1);
+            self.field.el_operation(lhs, rhs)
+        }
+
+        fn giant_step_jump(&self, base: &u128) -> u128 {
+            self.field.giant_step_jump(base)
+        }
+
+        fn process_result(&self, baby: &u128, giant: &u128) -> u128 {
+            self.field.process_result(baby, giant)
+        }
+    }
 
     fn test_nums(base: u128, pow: u128, modulo: u128) {
         let mut field = U128Field::new(modulo);
@@ -100,8 +260,47 @@ mod tests {
         assert_eq!(mod_exp(7, 13, 11), 2);
     }
 
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3, 7), Some(5));
+        assert_eq!(mod_inverse(22, 227), Some(31));
+        assert_eq!(mod_inverse(1, 2), Some(1));
+        assert_eq!(mod_inverse(10, 7), Some(5));
+        for a in 1..227 {
+            let inv = mod_inverse(a, 227).unwrap();
+            assert_eq!(a * inv % 227, 1);
+        }
+
+        // Not coprime
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(0, 7), None);
+        assert_eq!(mod_inverse(3, 0), None);
+
+        // Mersenne prime 2^127 - 1, products and sums would overflow u128
+        let p = (1_u128 << 127) - 1;
+        for a in [2, 3, 0xdead_beef, p - 1, p / 3, 1 << 100] {
+            let inv = mod_inverse(a, p).unwrap();
+            assert!(inv < p);
+            assert_eq!(mul_mod(a, inv, p), 1);
+        }
+        let inv = mod_inverse(u128::MAX - 1, u128::MAX).unwrap();
+        assert_eq!(mul_mod(u128::MAX - 1, inv, u128::MAX), 1);
+    }
+
+    #[test]
+    fn test_baby_steps_invariant() {
+        let mut field = U128Field::new(227);
+        field.baby_steps(&22);
+
+        assert_eq!(field.baby_steps.len() as u128, field.steps_count);
+        for k in 0..field.steps_count {
+            assert_eq!(field.in_baby_steps(&mod_exp(22, k, 227)), Some(k));
+        }
+    }
+
     #[test]
     fn test_baby_step_giant_step_small_values() {
+        test_nums(3, 0, 7);
         test_nums(3, 4, 7);
         test_nums(3, 13, 17);
     }
@@ -122,6 +321,123 @@ mod tests {
         assert_eq!(result, None);
     }
 
+    #[test]
+    fn test_run_mod() {
+        // 227 - 1 = 2 * 113, 22 generates the full group
+        let (base, modulo) = (22, 227);
+        for pow in [0, 1, 2, 109, 110, 111, 225] {
+            let target = mod_exp(base, pow, modulo);
+            let full = U128Field::new(modulo).run(base, target).unwrap();
+
+            let mut field = U128Field::new(modulo);
+            assert_eq!(field.run_mod(base, target, 2), Some(full % 2));
+            assert_eq!(field.run_mod(base, target, 113), Some(full % 113));
+        }
+
+        // Order must divide the group order
+        assert_eq!(U128Field::new(modulo).run_mod(base, 1, 3), None);
+    }
+
+    #[test]
+    fn test_run_typed() {
+        let mut field = U128Field::new(227);
+        let x = field.run_typed(22, mod_exp(22, 109, 227)).unwrap();
+        assert_eq!(u128::from(x), 109);
+    }
+
+    #[test]
+    fn test_scalar_hooks() {
+        let field = U128Field::new(227);
+        assert_eq!(field.scalar_add(&109, &7), 116);
+        assert_eq!(field.scalar_sub(&109, &7), 102);
+    }
+
+    #[test]
+    fn test_default_scalar_mul() {
+        let field = U128Field::new(227);
+        for k in [0, 1, 2, 109, 1000] {
+            assert_eq!(field.scalar_mul(&22, k), mod_exp(22, k as u128, 227));
+        }
+    }
+
+    #[test]
+    fn test_run_in_range() {
+        // 22 generates the full group of order 226, so logs repeat every 226
+        let target = mod_exp(22, 109, 227);
+
+        let mut field = U128Field::new(227);
+        assert_eq!(field.run_in_range(22, target, 0, 226), Some(109));
+        assert_eq!(field.run_in_range(22, target, 110, 400), Some(109 + 226));
+        assert_eq!(field.run_in_range(22, target, 110, 300), None);
+
+        // Range lower bound inside a giant step
+        assert_eq!(field.run_in_range(22, target, 100, 110), Some(109));
+    }
+
+    #[test]
+    fn test_small_order_base_exits_early() {
+        // 226 ≡ -1 (mod 227) has order 2, its jump is the identity
+        let mut field = CountingField {
+            field: U128Field::new(227),
+            ops: Cell::new(0),
+        };
+        assert_eq!(field.run(226, 5), None);
+        assert_eq!(field.ops.get(), 0);
+
+        // 2 has order 5 mod 31 and 3 isn't in its subgroup, the walk cycles
+        // back to the target after 5 of the 6 giant steps
+        let mut field = CountingField {
+            field: U128Field::new(31),
+            ops: Cell::new(0),
+        };
+        assert_eq!(field.run(2, 3), None);
+        assert_eq!(field.ops.get(), 5);
+    }
+
+    #[test]
+    fn test_run_many_dedups_targets() {
+        let (t1, t2) = (mod_exp(22, 109, 227), mod_exp(22, 7, 227));
+        let mut field = CountingField {
+            field: U128Field::new(227),
+            ops: Cell::new(0),
+        };
+        assert_eq!(field.run_many(22, &[t1, t2]), [Some(109), Some(7)]);
+        let unique_ops = field.ops.replace(0);
+
+        assert_eq!(
+            field.run_many(22, &[t1, t2, t1, t2, t1]),
+            [Some(109), Some(7), Some(109), Some(7), Some(109)]
+        );
+        assert_eq!(field.ops.get(), unique_ops);
+    }
+
+    #[test]
+    fn test_clear_cofactor() {
+        // 227 = 2·113 + 1 is a safe prime, squaring maps into the order 113 subgroup
+        let mut field = U128Field::new(227).with_cofactor(2);
+        assert_eq!(field.clear_cofactor(&22), 22 * 22 % 227);
+
+        for pow in [0, 50, 112, 113 + 50, 225] {
+            let target = mod_exp(22, pow, 227);
+            assert_eq!(field.run_cleared(22, target), Some(pow % 113));
+        }
+    }
+
+    #[test]
+    fn test_same_subgroup() {
+        // 227 = 2·113 + 1, squares form the order 113 subgroup, 226 has order 2
+        let mut field = U128Field::new(227).with_cofactor(2);
+        let square = 22 * 22 % 227;
+        assert!(field.same_subgroup(&22, &square));
+        assert!(!field.same_subgroup(&square, &22));
+        assert!(!field.same_subgroup(&226, &22));
+
+        assert_eq!(field.try_run(square, 22), Err(BsgsError::NoSolution));
+        assert_eq!(field.try_run(226, 22), Err(BsgsError::NoSolution));
+        assert_eq!(field.try_run(square, mod_exp(square, 50, 227)), Ok(50));
+        assert_eq!(field.try_run(22, mod_exp(22, 109, 227)), Ok(109));
+    }
+
     #[test]
     fn test_large_values() {
         test_nums(109768395, 121383451, 716982481063);
@@ -1,7 +1,27 @@
+/// `log::debug!` when the `logging` feature is on, nothing otherwise
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "logging")]
+        log::debug!($($arg)*);
+    };
+}
+
 pub mod baby_giant;
 pub use baby_giant::BabyGiantOps;
 
+pub mod config;
+pub use config::BsgsConfig;
+
+pub mod group;
+pub use group::{Group, GroupBabyGiant};
+
+pub mod error;
+pub use error::{BsgsError, FailureReason, ParsePointError};
+
 pub mod impls {
+    pub mod frozen;
     pub mod grumpkin;
+    #[cfg(feature = "spill")]
+    pub mod hybrid;
     pub mod u128_ff;
 }
@@ -0,0 +1,7 @@
+//! Compile-fail checks for the typed scalar API
+
+#[test]
+fn typed_scalars() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
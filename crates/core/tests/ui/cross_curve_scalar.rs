@@ -0,0 +1,7 @@
+use baby_giant_core::impls::{grumpkin::GrumpkinScalar, u128_ff::U128Scalar};
+
+fn takes_grumpkin(_: GrumpkinScalar) {}
+
+fn main() {
+    takes_grumpkin(U128Scalar(5));
+}
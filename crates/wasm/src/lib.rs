@@ -1,9 +1,11 @@
 mod utils;
 use ark_grumpkin::{Affine, Fr};
+use ark_serialize::CanonicalDeserialize;
 use baby_giant_core::{
     impls::grumpkin::{self, g, GrumpkinBabyGiant},
-    BabyGiantOps,
+    BabyGiantOps, FailureReason, ParsePointError,
 };
+use std::cell::{Cell, RefCell};
 use utils::set_panic_hook;
 use wasm_bindgen::prelude::*;
 
@@ -17,12 +19,33 @@ extern "C" {
     fn log(s: String);
 }
 
+thread_local! {
+    // Solver kept in module state so its table survives across calls
+    static SOLVER: RefCell<Option<GrumpkinBabyGiant>> = const { RefCell::new(None) };
+    // Tables built into SOLVER so far, shows whether a call rebuilt it
+    static TABLE_BUILDS: Cell<u64> = const { Cell::new(0) };
+}
+
 #[wasm_bindgen]
 pub fn greet() {
     set_panic_hook();
     log("Hello - BSGS wasm.".into());
 }
 
+/// Routes Rust panics to `console.error` with their message, the solving entry points
+/// also install it on first use
+#[wasm_bindgen]
+pub fn grumpkin_init_panic_hook() {
+    set_panic_hook();
+}
+
+/// Panics with message, for checking from JS that panics reach the console readably
+#[wasm_bindgen]
+pub fn grumpkin_panic(message: &str) {
+    set_panic_hook();
+    panic!("{}", message);
+}
+
 #[wasm_bindgen]
 pub fn grumpkin_mul() {
     let x: Fr = 4294967295_u64.into();
@@ -31,38 +54,197 @@ pub fn grumpkin_mul() {
 
 #[wasm_bindgen]
 pub fn baby_steps() -> Vec<String> {
+    set_panic_hook();
     let mut grumpy_bsgs = GrumpkinBabyGiant::new(65536);
     grumpy_bsgs.baby_steps(&g());
+    let keys = grumpy_bsgs
+        .get_baby_steps()
+        .keys()
+        .map(|key| grumpkin::key_to_fq(key).to_string())
+        .collect();
+
+    SOLVER.set(Some(grumpy_bsgs));
+    TABLE_BUILDS.set(TABLE_BUILDS.get() + 1);
+    keys
+}
+
+/// x-coordinates of the baby steps k·(x, y) for k in 0..steps, for a base other than `g()`
+/// Empty for invalid coordinates or zero steps
+#[wasm_bindgen]
+pub fn baby_steps_for(x: &str, y: &str, steps: u64) -> Vec<String> {
+    set_panic_hook();
+    let (Ok(base), Ok(mut grumpy_bsgs)) = (
+        grumpkin::try_grumpkin_str_to_point(x, y),
+        GrumpkinBabyGiant::try_new(steps),
+    ) else {
+        return Vec::new();
+    };
+
+    grumpy_bsgs.baby_steps(&base);
     grumpy_bsgs
         .get_baby_steps()
-        .clone()
-        .into_keys()
-        .map(|x| x.to_string())
+        .keys()
+        .map(|key| grumpkin::key_to_fq(key).to_string())
         .collect()
 }
 
+/// Builds the baby step table for `g()` once and keeps it for `grumpkin_solve`
+/// Zero steps drops any previously built table
 #[wasm_bindgen]
-pub fn grumpkin_point(x_num: u64) -> String {
-    let x: Fr = x_num.into();
+pub fn grumpkin_init(steps: u64) {
+    set_panic_hook();
+    let solver = GrumpkinBabyGiant::try_new(steps)
+        .ok()
+        .map(|mut grumpy_bsgs| {
+            grumpy_bsgs.baby_steps(&g());
+            grumpy_bsgs
+        });
 
-    let Affine { x, y, infinity: _ } = (g() * x).into();
-    x.to_string() + "|" + &y.to_string()
+    if solver.is_some() {
+        TABLE_BUILDS.set(TABLE_BUILDS.get() + 1);
+    }
+    SOLVER.set(solver);
 }
 
+/// Solves the point (x, y) against the table built by `grumpkin_init`
+/// Returns the decimal scalar, or an empty string for an invalid point, no built table or
+/// no solution
 #[wasm_bindgen]
-pub fn grumpkin_log_test(x_num: u64) -> u64 {
-    let x: Fr = if x_num == 0 {
-        4294967295_u64.into()
-    } else {
-        x_num.into()
+pub fn grumpkin_solve(x: &str, y: &str) -> String {
+    set_panic_hook();
+    let Ok(target) = grumpkin::try_grumpkin_str_to_point(x, y) else {
+        return String::new();
     };
 
-    let target: Affine = (g() * x).into();
+    SOLVER.with_borrow(|solver| {
+        solver
+            .as_ref()
+            .and_then(|solver| solver.solve(&g(), target))
+            .map(|res| res.to_string())
+            .unwrap_or_default()
+    })
+}
+
+/// Name of the `FailureReason` for solving (x, y) against the table built by
+/// `grumpkin_init`, e.g. "OutOfRange", or "Solvable" when the table solves it
+/// Empty string for unparsable coordinates
+#[wasm_bindgen]
+pub fn grumpkin_diagnose(x: &str, y: &str) -> String {
+    set_panic_hook();
+    let target = match grumpkin::try_grumpkin_str_to_point(x, y) {
+        Ok(target) => target,
+        Err(ParsePointError::OffCurve) => return format!("{:?}", FailureReason::OffCurve),
+        Err(_) => return String::new(),
+    };
+
+    SOLVER.with_borrow(|solver| {
+        let reason = solver
+            .as_ref()
+            .map(|solver| solver.diagnose(&g(), &target))
+            .unwrap_or(FailureReason::Unknown);
+        format!("{:?}", reason)
+    })
+}
+
+/// JSON `{ "entries": n, "approx_bytes": b, "builds": k }` describing the persisted baby
+/// step table, builds counting every table built into it so far
+#[wasm_bindgen]
+pub fn grumpkin_table_info() -> String {
+    SOLVER.with_borrow(|solver| {
+        let (entries, approx_bytes) = match solver {
+            Some(solver) => (solver.get_baby_steps().len(), solver.approx_table_bytes()),
+            None => (0, 0),
+        };
+        let builds = TABLE_BUILDS.get();
+        format!(r#"{{ "entries": {entries}, "approx_bytes": {approx_bytes}, "builds": {builds} }}"#)
+    })
+}
+
+#[wasm_bindgen]
+pub fn grumpkin_point(x_num: u64) -> String {
+    let Affine { x, y, infinity: _ } = grumpkin::grumpkin_scalar_base(x_num);
+    x.to_string() + "|" + &y.to_string()
+}
+
+#[wasm_bindgen]
+pub fn grumpkin_log_test(x_num: u64) -> u64 {
+    set_panic_hook();
+    let x_num = if x_num == 0 { 4294967295_u64 } else { x_num };
+    let target = grumpkin::grumpkin_scalar_base(x_num);
 
     grumpkin::grumpkin_bsgs_32(target)
 }
 
+/// Solves (x, y) with a `steps` sized table, throwing a JS error for bad coordinates,
+/// off-curve points, zero steps or targets without a solution below steps²
+#[wasm_bindgen]
+pub fn grumpkin_bsgs_checked(x: &str, y: &str, steps: u64) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let mut grumpy_bsgs =
+        GrumpkinBabyGiant::try_new(steps).map_err(|err| JsError::new(&err.to_string()))?;
+
+    let target =
+        grumpkin::try_grumpkin_str_to_point(x, y).map_err(|err| JsError::new(&err.to_string()))?;
+
+    match grumpy_bsgs.run(g(), target) {
+        Some(res) => Ok(JsValue::from_str(&res.to_string())),
+        None => Err(JsError::new("no scalar found below steps²").into()),
+    }
+}
+
+/// Solves a point given as arkworks `CanonicalSerialize` bytes, compressed or not,
+/// with a `steps` sized table. Returns the decimal scalar, or an empty string for bytes
+/// that aren't a valid point, zero steps or no solution
+#[wasm_bindgen]
+pub fn grumpkin_bsgs_bytes(point_bytes: &[u8], steps: u64) -> String {
+    set_panic_hook();
+    let target = Affine::deserialize_compressed(point_bytes)
+        .or_else(|_| Affine::deserialize_uncompressed(point_bytes));
+    let (Ok(target), Ok(mut grumpy_bsgs)) = (target, GrumpkinBabyGiant::try_new(steps)) else {
+        return String::new();
+    };
+
+    grumpy_bsgs
+        .run(g(), target)
+        .map(|res| res.to_string())
+        .unwrap_or_default()
+}
+
+/// Solves (x, y) against `g()` with a 2^16 step table, 0 for an invalid point or no solution
 #[wasm_bindgen]
 pub fn grumpkin_bsgs_str_(x: &str, y: &str) -> u64 {
-    grumpkin::grumpkin_bsgs_32(grumpkin::grumpkin_str_to_point(x, y))
+    set_panic_hook();
+    grumpkin::try_grumpkin_str_to_point(x, y).map_or(0, grumpkin::grumpkin_bsgs_32)
+}
+
+/// `grumpkin_bsgs_str_` as a 0x-prefixed hex string, matching other field values in JS
+/// Empty string for an invalid point
+#[wasm_bindgen]
+pub fn grumpkin_bsgs_hex(x: &str, y: &str) -> String {
+    set_panic_hook();
+    grumpkin::try_grumpkin_str_to_point(x, y)
+        .map(|target| format!("{:#x}", grumpkin::grumpkin_bsgs_32(target)))
+        .unwrap_or_default()
+}
+
+/// Scalar as the hex of its 8 big endian bytes, see `grumpkin::scalar_to_bytes`
+#[wasm_bindgen]
+pub fn grumpkin_scalar_to_hex(s: u64) -> String {
+    grumpkin::scalar_to_bytes(s)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Inverse of `grumpkin_scalar_to_hex`, undefined unless given 16 hex digits
+#[wasm_bindgen]
+pub fn grumpkin_scalar_from_hex(hex: &str) -> Option<u64> {
+    if hex.len() != 16 || !hex.is_ascii() {
+        return None;
+    }
+    let bytes = (0..16)
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    grumpkin::scalar_from_bytes(&bytes)
 }
@@ -3,6 +3,14 @@
 #![cfg(target_arch = "wasm32")]
 
 extern crate wasm_bindgen_test;
+use ark_serialize::CanonicalSerialize;
+use baby_giant_core::impls::grumpkin::grumpkin_scalar_base;
+use baby_giant_wasm::{
+    baby_steps, baby_steps_for, grumpkin_bsgs_bytes, grumpkin_bsgs_checked, grumpkin_bsgs_hex,
+    grumpkin_bsgs_str_, grumpkin_diagnose, grumpkin_init, grumpkin_init_panic_hook, grumpkin_panic,
+    grumpkin_point, grumpkin_scalar_from_hex, grumpkin_scalar_to_hex, grumpkin_solve,
+    grumpkin_table_info,
+};
 use wasm_bindgen_test::*;
 
 wasm_bindgen_test_configure!(run_in_browser);
@@ -11,3 +19,139 @@ wasm_bindgen_test_configure!(run_in_browser);
 fn pass() {
     assert_eq!(1 + 1, 2);
 }
+
+#[wasm_bindgen_test]
+fn table_info_after_baby_steps() {
+    baby_steps();
+    let info = grumpkin_table_info();
+    assert!(info.contains(r#""entries": 65536"#), "{}", info);
+    assert!(!info.contains(r#""approx_bytes": 0 "#), "{}", info);
+}
+
+fn point_coords(x_num: u64) -> (String, String) {
+    let point = grumpkin_point(x_num);
+    let (x, y) = point.split_once('|').unwrap();
+    (x.to_string(), y.to_string())
+}
+
+#[wasm_bindgen_test]
+fn solve_reuses_initialized_table() {
+    grumpkin_init(16);
+    let info = grumpkin_table_info();
+
+    let (x, y) = point_coords(200);
+    assert_eq!(grumpkin_solve(&x, &y), "200");
+    let (x, y) = point_coords(255);
+    assert_eq!(grumpkin_solve(&x, &y), "255");
+
+    // Beyond 16² the persisted small table can't reach
+    let (x, y) = point_coords(256);
+    assert_eq!(grumpkin_solve(&x, &y), "");
+
+    // Same table and build count after the solves, only init builds
+    assert_eq!(grumpkin_table_info(), info);
+    assert!(info.contains(r#""entries": 16"#), "{}", info);
+    grumpkin_init(16);
+    assert_ne!(grumpkin_table_info(), info);
+}
+
+#[wasm_bindgen_test]
+fn bsgs_checked() {
+    let (x, y) = point_coords(1000);
+    assert_eq!(grumpkin_bsgs_checked(&x, &y, 64).unwrap(), "1000");
+
+    // Unsolvable with a table this small
+    assert!(grumpkin_bsgs_checked(&x, &y, 16).is_err());
+    // Bad parameters
+    assert!(grumpkin_bsgs_checked(&x, &y, 0).is_err());
+    // Unparsable and off-curve coordinates
+    assert!(grumpkin_bsgs_checked("not a number", &y, 64).is_err());
+    assert!(grumpkin_bsgs_checked("1", "2", 64).is_err());
+}
+
+#[wasm_bindgen_test]
+fn bsgs_hex_matches_decimal() {
+    let (x, y) = point_coords(48879);
+    let hex = grumpkin_bsgs_hex(&x, &y);
+    assert_eq!(hex, "0xbeef");
+    assert_eq!(
+        u64::from_str_radix(hex.trim_start_matches("0x"), 16).unwrap(),
+        grumpkin_bsgs_str_(&x, &y)
+    );
+}
+
+#[wasm_bindgen_test]
+fn diagnose_reasons() {
+    grumpkin_init(16);
+    let (x, y) = point_coords(1000);
+    assert_eq!(grumpkin_diagnose(&x, &y), "OutOfRange");
+    let (x, y) = point_coords(200);
+    assert_eq!(grumpkin_diagnose(&x, &y), "Solvable");
+    assert_eq!(grumpkin_diagnose("1", "2"), "OffCurve");
+    assert_eq!(grumpkin_diagnose("abc", "2"), "");
+}
+
+#[wasm_bindgen_test]
+fn bsgs_bytes() {
+    let point = grumpkin_scalar_base(1000);
+    let mut compressed = Vec::new();
+    point.serialize_compressed(&mut compressed).unwrap();
+    let mut uncompressed = Vec::new();
+    point.serialize_uncompressed(&mut uncompressed).unwrap();
+
+    assert_eq!(grumpkin_bsgs_bytes(&compressed, 64), "1000");
+    assert_eq!(grumpkin_bsgs_bytes(&uncompressed, 64), "1000");
+    assert_eq!(grumpkin_bsgs_bytes(&compressed, 0), "");
+    assert_eq!(grumpkin_bsgs_bytes(&compressed[..16], 64), "");
+    assert_eq!(grumpkin_bsgs_bytes(&[0xff; 32], 64), "");
+}
+
+#[wasm_bindgen_test]
+fn solve_rejects_bad_points_cleanly() {
+    grumpkin_init(16);
+    assert_eq!(grumpkin_solve("abc", "1"), "");
+    assert_eq!(grumpkin_solve("1", "2"), "");
+}
+
+#[wasm_bindgen_test]
+fn baby_steps_for_other_base() {
+    // Base 7·G, so entry k is 7k·G
+    let (x, y) = point_coords(7);
+    let keys = baby_steps_for(&x, &y, 32);
+    assert_eq!(keys.len(), 32);
+    for k in [1, 5, 31] {
+        assert!(keys.contains(&point_coords(7 * k).0));
+    }
+    assert!(!keys.contains(&point_coords(5).0));
+
+    assert!(baby_steps_for(&x, &y, 0).is_empty());
+    assert!(baby_steps_for("1", "2", 32).is_empty());
+}
+
+#[wasm_bindgen_test]
+#[should_panic(expected = "deliberate panic from the test")]
+fn panic_surfaces_message() {
+    grumpkin_init_panic_hook();
+    grumpkin_panic("deliberate panic from the test");
+}
+
+#[wasm_bindgen_test]
+fn invalid_point_returns_sentinel() {
+    grumpkin_init_panic_hook();
+    assert_eq!(grumpkin_bsgs_str_("not a number", "1"), 0);
+    assert_eq!(grumpkin_bsgs_str_("1", "1"), 0);
+    assert_eq!(grumpkin_bsgs_hex("not a number", "1"), "");
+}
+
+#[wasm_bindgen_test]
+fn scalar_hex_roundtrip() {
+    assert_eq!(grumpkin_scalar_to_hex(0xbeef), "000000000000beef");
+    for s in [0, 1, 0xbeef, u64::MAX] {
+        assert_eq!(
+            grumpkin_scalar_from_hex(&grumpkin_scalar_to_hex(s)),
+            Some(s)
+        );
+    }
+    assert_eq!(grumpkin_scalar_from_hex("beef"), None);
+    assert_eq!(grumpkin_scalar_from_hex("zz0000000000beef"), None);
+}